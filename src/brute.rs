@@ -17,7 +17,12 @@
 use log::{debug, info, trace};
 
 use crate::{Result, SolverError, Problem, Solution, ConstraintKind, ObjectiveKind,
-    VariableKind};
+    VariableKind, Expression, Variable, Constraint as ProblemConstraint, SolveResult, SolveStatus,
+    SolveStats, SolverOptions, Progress, simplex};
+use crate::options::Interrupt;
+
+/// The number of nodes checked between each progress report
+const PROGRESS_INTERVAL: u64 = 10_000;
 
 struct Var {
     value: i32,
@@ -36,17 +41,123 @@ struct Bound {
 }
 
 struct Constraint {
-    coeffs: Vec<f64>,
+    coeffs: Vec<(usize, f64)>,
     kind: ConstraintKind,
     constant: f64,
 }
 
 struct Objective {
-    coeffs: Vec<f64>,
+    coeffs: Vec<(usize, f64)>,
     kind: ObjectiveKind,
 }
 
-pub fn solve(problem: &Problem) -> Result<Solution> {
+/// The continuous variables of a mixed integer problem.  For each assignment of the
+/// integer variables, the problem over the remaining continuous variables is solved
+/// with simplex.
+struct ContinuousPart<'a> {
+    problem: &'a Problem,
+    interrupt: Interrupt,
+
+    /// The options for each subproblem, with the iteration limit reduced by the
+    /// iterations already used in the search
+    options: SolverOptions,
+    iteration_limit: Option<u64>,
+
+    /// The variable in the continuous problem for each problem variable
+    variables: Vec<Option<Variable>>,
+    lp: Problem,
+}
+
+impl<'a> ContinuousPart<'a> {
+    fn new(
+        problem: &'a Problem,
+        options: &SolverOptions,
+        interrupt: &Interrupt,
+    ) -> Result<Option<ContinuousPart<'a>>> {
+        let mut lp = Problem::new();
+        let mut variables = Vec::new();
+        for var in problem.variables() {
+            match var.kind() {
+                VariableKind::Continuous => {
+                    let variable = lp.add_variable(VariableKind::Continuous);
+                    let (lower, upper) = problem.bounds(*var);
+                    lp.set_bounds(variable, lower, upper)?;
+                    variables.push(Some(variable));
+                },
+                VariableKind::Integer(..) => variables.push(None),
+            }
+        }
+
+        if lp.num_variables() == 0 {
+            return Ok(None);
+        }
+
+        // progress is reported for the search as a whole, not for each subproblem
+        let interrupt = interrupt.without_progress();
+        Ok(Some(ContinuousPart {
+            problem,
+            interrupt,
+            options: options.clone(),
+            iteration_limit: options.iteration_limit,
+            variables,
+            lp,
+        }))
+    }
+
+    /// Solves for the continuous variables with the integer variables fixed by `vars`,
+    /// given the number of iterations already used.  The solution, if any, includes the
+    /// values of all variables.
+    fn solve(&mut self, vars: &[Var], iterations: u64) -> Result<SolveResult> {
+        self.lp.clear_constraints();
+
+        for constraint in self.problem.constraints() {
+            let (expr, shift) = substitute(&self.variables, constraint.expr(), vars);
+            let kind = match constraint.kind() {
+                ConstraintKind::Range(lower) => ConstraintKind::Range(lower - shift),
+                kind => kind,
+            };
+            let constant = constraint.constant() - shift;
+            self.lp.add_constraint(ProblemConstraint::new(expr, kind, constant))?;
+        }
+
+        let (obj_expr, obj_kind) = self.problem.objective().unwrap();
+        let (expr, offset) = substitute(&self.variables, obj_expr, vars);
+        self.lp.set_objective(expr, obj_kind);
+
+        self.options.iteration_limit = self.iteration_limit
+            .map(|limit| limit.saturating_sub(iterations));
+        let result = simplex::solve(&self.lp, &self.options, &self.interrupt)?;
+        let solution = result.solution().map(|solution| {
+            let coeffs = self.variables.iter().zip(vars.iter()).map(|(variable, var)| {
+                match variable {
+                    None => var.value as f64,
+                    Some(variable) => solution.value_of(*variable),
+                }
+            }).collect();
+            Solution::new(coeffs, solution.objective().map(|objective| objective + offset))
+        });
+
+        Ok(SolveResult::new(result.status(), solution, result.stats().clone()))
+    }
+}
+
+/// The state of the brute force search
+struct Search<'a> {
+    options: &'a SolverOptions,
+    interrupt: &'a Interrupt,
+    constraints: Vec<Constraint>,
+    objective: Objective,
+    continuous: Option<ContinuousPart<'a>>,
+    solution: Option<Vec<f64>>,
+    best_value: f64,
+    stats: SolveStats,
+}
+
+pub fn solve(
+    problem: &Problem,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
     let (obj_expr, obj_kind) = match problem.objective() {
         None => return SolverError::invalid_objective("Must set an objective for Integer problems."),
         Some((expr, kind)) => (expr, kind),
@@ -59,36 +170,34 @@ pub fn solve(problem: &Problem) -> Result<Solution> {
         }
     }).collect();
 
-    let num_vars = problem.num_variables() as u32;
     let constraints: Vec<Constraint> = problem.constraints().iter().map(|constraint| {
-        let mut coeffs = Vec::new();
-        for i in 0..num_vars {
-            coeffs.push(constraint.expr().get(i));
-        }
         Constraint {
-            coeffs,
+            coeffs: sparse_coeffs(constraint.expr()),
             kind: constraint.kind(),
             constant: constraint.constant(),
         }
     }).collect();
 
-
-    let mut obj_coeffs = Vec::new();
-    for i in 0..num_vars {
-        obj_coeffs.push(obj_expr.get(i));
-    }
-
     let objective = Objective {
-        coeffs: obj_coeffs,
+        coeffs: sparse_coeffs(obj_expr),
         kind: obj_kind
     };
 
-    let mut solution = vec![0; num_vars as usize];
-    let mut best_obj = match objective.kind {
-        ObjectiveKind::Maximize => std::f64::MIN,
-        ObjectiveKind::Minimize => std::f64::MAX,
+    let best_value = match objective.kind {
+        ObjectiveKind::Maximize => f64::MIN,
+        ObjectiveKind::Minimize => f64::MAX,
+    };
+
+    let mut search = Search {
+        options,
+        interrupt,
+        constraints,
+        objective,
+        continuous: ContinuousPart::new(problem, options, interrupt)?,
+        solution: None,
+        best_value,
+        stats: SolveStats::default(),
     };
-    let initial_best = best_obj;
 
     let mut vars = Vec::new();
     for bound in bounds {
@@ -98,55 +207,129 @@ pub fn solve(problem: &Problem) -> Result<Solution> {
     }
 
     info!("Setup problem in vector form.  Brute force searching...");
-    check_combinations(&mut solution, &mut best_obj, &mut vars, &constraints, &objective, 0);
+    let stopped = search.check_combinations(&mut vars, 0)?;
 
-    if initial_best == best_obj {
-        return SolverError::infeasible("No Solution exists.");
-    }
+    let best_value = search.best_value;
+    let solution = search.solution.map(|coeffs| Solution::new(coeffs, Some(best_value)));
+    let status = match (stopped, &solution) {
+        (Some(status), None) => status,
+        (Some(status), Some(_)) => {
+            info!("Stopped with a feasible solution: {:?}", status);
+            SolveStatus::Feasible
+        },
+        (None, None) => {
+            info!("No Solution exists.");
+            SolveStatus::Infeasible
+        },
+        (None, Some(_)) => SolveStatus::Optimal,
+    };
 
-    let coeffs = solution.iter().map(|c| *c as f64).collect();
+    Ok(SolveResult::new(status, solution, search.stats))
+}
+
+/// Returns the continuous part of `expr`, and the value of the integer part
+fn substitute(
+    variables: &[Option<Variable>],
+    expr: &Expression,
+    vars: &[Var]
+) -> (Expression, f64) {
+    let mut result = Expression::default();
+    let mut constant = 0.0;
+    for (index, coeff) in expr.iter() {
+        let index = *index as usize;
+        match variables[index] {
+            None => constant += *coeff * vars[index].value as f64,
+            Some(variable) => result.add_term(*coeff, variable),
+        }
+    }
 
-    Ok(Solution::new(coeffs, Some(best_obj)))
+    (result, constant)
 }
 
-fn check_combinations(
-    solution: &mut Vec<i32>,
-    best_value: &mut f64,
-    vars: &mut Vec<Var>,
-    constraints: &[Constraint],
-    objective: &Objective,
-    cur_index: usize,
-) {
-    for val in vars[cur_index].bound.min..=vars[cur_index].bound.max {
-        vars[cur_index].value = val;
-        debug!("Checking with coefficients: {:?}", vars);
+impl<'a> Search<'a> {
+    /// Checks every combination of values for the variables from `cur_index` onwards.
+    /// Returns the status if the search had to stop early.
+    fn check_combinations(
+        &mut self,
+        vars: &mut [Var],
+        cur_index: usize,
+    ) -> Result<Option<SolveStatus>> {
+        if cur_index == vars.len() {
+            return self.check(vars);
+        }
 
-        if meets_constraints(vars, constraints) {
-            let test = get_constraint_value(vars, &objective.coeffs);
-            debug!("  Constraints met, got objective value: {}", test);
+        for val in vars[cur_index].bound.min..=vars[cur_index].bound.max {
+            vars[cur_index].value = val;
 
-            let best = match objective.kind {
-                ObjectiveKind::Minimize => test < *best_value,
-                ObjectiveKind::Maximize => test > *best_value,
-            };
+            let stopped = self.check_combinations(vars, cur_index + 1)?;
+            if stopped.is_some() { return Ok(stopped); }
+        }
 
-            if best {
-                debug!("  Values tested are new best.");
-                *best_value = test;
-                for i in 0..vars.len() {
-                    solution[i] = vars[i].value;
-                }
+        Ok(None)
+    }
+
+    /// Checks the current values of `vars`, keeping them if they are the new best
+    fn check(&mut self, vars: &[Var]) -> Result<Option<SolveStatus>> {
+        debug!("Checking with coefficients: {:?}", vars);
+        if let Some(status) = self.interrupt.check() {
+            info!("Stopping after {} nodes: {:?}", self.stats.nodes, status);
+            return Ok(Some(status));
+        }
+        self.stats.nodes += 1;
+
+        if self.stats.nodes % PROGRESS_INTERVAL == 0 {
+            let objective = self.solution.as_ref().map(|_| self.best_value);
+            let progress = Progress::Nodes { nodes: self.stats.nodes, objective };
+            if let Some(status) = self.interrupt.report(progress) {
+                return Ok(Some(status));
             }
         }
 
-        if cur_index < vars.len() - 1 {
-            check_combinations(solution, best_value, vars, constraints, objective, cur_index + 1);
+        let (test, values) = match &mut self.continuous {
+            None => {
+                let tol = self.options.tolerances.feasibility;
+                if !meets_constraints(vars, &self.constraints, tol) { return Ok(None); }
+                let values = vars.iter().map(|var| var.value as f64).collect();
+                (get_constraint_value(vars, &self.objective.coeffs), values)
+            },
+            Some(continuous) => {
+                let result = continuous.solve(vars, self.stats.iterations)?;
+                self.stats.add(result.stats());
+                match (result.status(), result.solution()) {
+                    (SolveStatus::Optimal, Some(solution)) =>
+                        (solution.objective().unwrap_or(0.0), solution.iter().cloned().collect()),
+                    (SolveStatus::Infeasible, _) => return Ok(None),
+                    // the subproblem only stops early at the iteration limit or when
+                    // interrupted, and its incumbent isn't this node's optimum
+                    (SolveStatus::Feasible, _) => {
+                        let status = self.interrupt.check();
+                        return Ok(Some(status.unwrap_or(SolveStatus::IterationLimit)));
+                    },
+                    (status, _) => return Ok(Some(status)),
+                }
+            }
+        };
+        debug!("  Constraints met, got objective value: {}", test);
+
+        let best = match self.objective.kind {
+            ObjectiveKind::Minimize => test < self.best_value,
+            ObjectiveKind::Maximize => test > self.best_value,
+        };
+
+        if best {
+            debug!("  Values tested are new best.");
+            self.best_value = test;
+            self.solution = Some(values);
+
+            let progress = Progress::Incumbent { nodes: self.stats.nodes, objective: test };
+            return Ok(self.interrupt.report(progress));
         }
+
+        Ok(None)
     }
 }
 
-fn meets_constraints(vars: &[Var], constraints: &[Constraint]) -> bool {
-    let tol = std::f32::EPSILON as f64;
+fn meets_constraints(vars: &[Var], constraints: &[Constraint], tol: f64) -> bool {
     for constraint in constraints {
         let constant = constraint.constant;
         let computed = get_constraint_value(vars, &constraint.coeffs);
@@ -156,6 +339,7 @@ fn meets_constraints(vars: &[Var], constraints: &[Constraint]) -> bool {
             ConstraintKind::GreaterThanOrEqualTo => computed > constant - tol,
             ConstraintKind::EqualTo => (computed - constant).abs() < tol,
             ConstraintKind::LessThanOrEqualTo => computed < constant + tol,
+            ConstraintKind::Range(lower) => computed > lower - tol && computed < constant + tol,
         };
 
         if !met { return false; }
@@ -164,10 +348,19 @@ fn meets_constraints(vars: &[Var], constraints: &[Constraint]) -> bool {
     true
 }
 
-fn get_constraint_value(vars: &[Var], constraint: &[f64]) -> f64 {
+fn sparse_coeffs(expr: &Expression) -> Vec<(usize, f64)> {
+    let mut coeffs: Vec<(usize, f64)> = expr.iter()
+        .filter(|(_, coeff)| **coeff != 0.0)
+        .map(|(index, coeff)| (*index as usize, *coeff))
+        .collect();
+    coeffs.sort_by_key(|(index, _)| *index);
+    coeffs
+}
+
+fn get_constraint_value(vars: &[Var], coeffs: &[(usize, f64)]) -> f64 {
     let mut total = 0.0;
-    for i in 0..vars.len() {
-        total += vars[i].value as f64 * constraint[i];
+    for (index, coeff) in coeffs {
+        total += vars[*index].value as f64 * coeff;
     }
 
     total
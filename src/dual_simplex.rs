@@ -0,0 +1,236 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use log::{info, warn};
+
+use crate::{Problem, Result, SolverError, SolveResult, SolveStatus, SolveStats, SolverOptions,
+    Progress};
+use crate::options::Interrupt;
+use crate::revised_simplex::{self, Revised};
+use crate::simplex::{BasisStatus, DEGENERATE_PIVOT_LIMIT};
+
+pub fn solve(
+    problem: &Problem,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
+    let objective_kind = match problem.objective_kind() {
+        None => {
+            return SolverError::invalid_objective("Must set an objective for simplex.");
+        }, Some(kind) => kind,
+    };
+
+    let basis = options.basis.as_ref();
+    let mut revised = Revised::new(problem, objective_kind, options.tolerances, basis)?;
+    let mut stats = SolveStats::default();
+
+    if !make_dual_feasible(&mut revised)? {
+        info!("Basis is not dual feasible, using the primal simplex method");
+        return revised_simplex::primal(&mut revised, stats, options, interrupt);
+    }
+
+    info!("Performing dual simplex solve");
+    let status = dual_simplex(&mut revised, &mut stats, options, interrupt)?;
+    if status != SolveStatus::Optimal {
+        return Ok(SolveResult::new(status, None, stats));
+    }
+
+    // the basis is now optimal, so this only removes any small dual infeasibilities
+    // and produces the solution
+    revised_simplex::primal(&mut revised, stats, options, interrupt)
+}
+
+/// Moves each non-basic column with a reduced cost of the wrong sign to its opposite
+/// bound, making the basis dual feasible.  Returns false if that isn't possible
+/// because a column has no opposite bound.
+fn make_dual_feasible(revised: &mut Revised) -> Result<bool> {
+    let y = revised.prices();
+    let tol = revised.tolerances.optimality;
+    let mut flips = Vec::new();
+    for col in 0..revised.columns.len() {
+        let cost = revised.reduced_cost(&y, col);
+        let (lower, upper) = (revised.lower[col], revised.upper[col]);
+        let (value, status) = match revised.statuses[col] {
+            BasisStatus::AtLower if cost < -tol => (upper, BasisStatus::AtUpper),
+            BasisStatus::AtUpper if cost > tol => (lower, BasisStatus::AtLower),
+            BasisStatus::Free if cost.abs() > tol => return Ok(false),
+            _ => continue,
+        };
+
+        if !value.is_finite() { return Ok(false); }
+        flips.push((col, value, status));
+    }
+
+    if flips.is_empty() { return Ok(true); }
+
+    for (col, value, status) in flips {
+        revised.values[col] = value;
+        revised.statuses[col] = status;
+    }
+    revised.refactor()?;
+    Ok(true)
+}
+
+/// Runs the dual simplex method from a dual feasible basis until the basis is also
+/// primal feasible, or shown to be infeasible.  If there is no iteration limit in the
+/// options, the iterations are limited based on the size of the problem.
+fn dual_simplex(
+    revised: &mut Revised,
+    stats: &mut SolveStats,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveStatus> {
+    let size = (revised.columns.len() + 1) * (revised.num_constraints() + 1);
+    let max_iterations = options.iteration_limit.unwrap_or(stats.iterations + size as u64);
+
+    let mut degenerate_pivots = 0;
+    loop {
+        info!("Iteration {}", stats.iterations);
+        if stats.iterations >= max_iterations {
+            warn!("Warning.  Failed to find solution after {} iterations", stats.iterations);
+            return Ok(SolveStatus::IterationLimit);
+        }
+
+        if let Some(status) = interrupt.check() {
+            warn!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            return Ok(status);
+        }
+
+        let bland = degenerate_pivots >= DEGENERATE_PIVOT_LIMIT;
+        if degenerate_pivots == DEGENERATE_PIVOT_LIMIT {
+            info!("Switching to Bland's rule after {} degenerate pivots", degenerate_pivots);
+        }
+
+        let (position, infeasibility) = match select_leaving(revised, bland) {
+            None => {
+                info!("All basic columns are feasible - solution is optimal");
+                break;
+            }, Some(leaving) => leaving,
+        };
+        info!("Selected leaving column {}", revised.basis[position]);
+
+        let mut unit = vec![0.0; revised.num_constraints()];
+        unit[position] = 1.0;
+        let row = revised.factor.btran(unit);
+        let (entering, ratio) = match select_entering(revised, &row, infeasibility, bland) {
+            None => {
+                info!("No entering column exists.  Problem is infeasible.");
+                return Ok(SolveStatus::Infeasible);
+            }, Some(entering) => entering,
+        };
+        info!("Selected entering column {}", entering);
+
+        if ratio <= revised.tolerances.pivot {
+            degenerate_pivots += 1;
+        } else {
+            degenerate_pivots = 0;
+        }
+
+        let alpha = revised.factor.ftran(revised.dense_column(entering));
+        revised.advance(entering, &alpha, infeasibility / alpha[position]);
+        revised.replace(position, entering, &alpha, infeasibility > 0.0)?;
+
+        stats.iterations += 1;
+        let progress = Progress::Simplex {
+            iteration: stats.iterations,
+            objective: revised.current_objective(2),
+            phase: 2,
+        };
+        if let Some(status) = interrupt.report(progress) {
+            info!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            return Ok(status);
+        }
+    }
+
+    info!("Dual simplex solve complete.");
+    Ok(SolveStatus::Optimal)
+}
+
+/// Selects the basic column furthest outside its bounds to leave the basis, or with
+/// Bland's rule the lowest infeasible column.  Returns its position in the basis and
+/// its infeasibility.
+fn select_leaving(revised: &Revised, bland: bool) -> Option<(usize, f64)> {
+    let mut best = None;
+    let mut best_infeasibility = 0.0;
+    for (position, col) in revised.basis.iter().enumerate() {
+        let infeasibility = revised.infeasibility(*col);
+        if infeasibility == 0.0 { continue; }
+
+        let better = if bland {
+            best.map_or(true, |(best_position, _)| *col < revised.basis[best_position])
+        } else {
+            infeasibility.abs() > best_infeasibility
+        };
+
+        if better {
+            best = Some((position, infeasibility));
+            best_infeasibility = infeasibility.abs();
+        }
+    }
+
+    best
+}
+
+/// Performs the dual ratio test for the basic column leaving with the given
+/// infeasibility, where `row` selects its row of the tableau.  The entering column
+/// must move the leaving column towards its bounds, and is the one whose reduced cost
+/// reaches zero first.  Returns the column along with the ratio.  With Bland's rule,
+/// ties are broken by the lowest column.
+fn select_entering(
+    revised: &Revised,
+    row: &[f64],
+    infeasibility: f64,
+    bland: bool,
+) -> Option<(usize, f64)> {
+    let y = revised.prices();
+    let tol = revised.tolerances.pivot;
+
+    let mut min = f64::INFINITY;
+    let mut best = None;
+    let mut best_alpha = 0.0;
+    for col in 0..revised.columns.len() {
+        let status = revised.statuses[col];
+        if status == BasisStatus::Basic || revised.lower[col] == revised.upper[col] { continue; }
+
+        let alpha = revised.dot(row, col);
+        if alpha.abs() <= tol { continue; }
+
+        // the entering column moves by infeasibility / alpha
+        let increases = (alpha > 0.0) == (infeasibility > 0.0);
+        let cost = revised.reduced_cost(&y, col);
+        let cost = match status {
+            BasisStatus::AtLower if increases => cost.max(0.0),
+            BasisStatus::AtUpper if !increases => (-cost).max(0.0),
+            BasisStatus::Free => cost.abs(),
+            _ => continue,
+        };
+
+        let ratio = cost / alpha.abs();
+        let better = if bland {
+            ratio < min - tol
+        } else {
+            ratio < min - tol || (ratio <= min + tol && alpha.abs() > best_alpha)
+        };
+
+        if better {
+            min = ratio;
+            best = Some(col);
+            best_alpha = alpha.abs();
+        }
+    }
+
+    best.map(|col| (col, min))
+}
@@ -16,15 +16,22 @@
 
 use log::{debug, info};
 
-use crate::{Matrix, Row, Col, Problem, Solution, Result, SolverError};
-
-pub fn solve(problem: &Problem) -> Result<Solution> {
+use crate::{Matrix, CsrMatrix, Row, Col, Problem, Solution, Result, SolverError, SolveResult,
+    SolveStatus, SolveStats, SolverOptions};
+use crate::options::Interrupt;
+
+pub fn solve(
+    problem: &Problem,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
     let matrix = setup_matrix(problem)?;
 
-    gaussian_elimination(matrix)
+    gaussian_elimination(matrix, options.tolerances.pivot, interrupt)
 }
 
-fn setup_matrix(problem: &Problem) -> Result<Matrix> {
+/// Builds the sparse augmented matrix, with the constants in the last column
+fn setup_matrix(problem: &Problem) -> Result<CsrMatrix> {
     if problem.objective_kind().is_some() {
         return SolverError::invalid_objective("Gaussian elimination does not accept\
                 an objective function.");
@@ -40,50 +47,61 @@ fn setup_matrix(problem: &Problem) -> Result<Matrix> {
 
     let height = problem.num_constraints();
     let width = problem.num_variables() + 1;
-    let mut coeffs = vec![0.0;width*height];
+    let mut entries = Vec::new();
 
     for (row, constraint) in problem.constraints().iter().enumerate() {
         use crate::ConstraintKind::*;
         match constraint.kind() {
-            GreaterThanOrEqualTo | LessThanOrEqualTo => {
-                return SolverError::invalid_constraint("Gaussian elimination only accepts\
-                        equality constraints.");
+            GreaterThanOrEqualTo | LessThanOrEqualTo | Range(_) => {
+                return SolverError::invalid_constraint(format!("Gaussian elimination only \
+                        accepts equality constraints, but constraint {} is an inequality.",
+                        problem.constraint_label(row)));
             }, EqualTo => (),
         }
 
         for (col, value) in constraint.expr().iter() {
-            coeffs[*col as usize + row * width] = *value;
+            entries.push((row, *col as usize, *value));
         }
-        coeffs[width - 1 + row * width] = constraint.constant();
+        entries.push((row, width - 1, constraint.constant()));
     }
 
-    Ok(Matrix::new(width, height, coeffs))
+    CsrMatrix::from_entries(width, height, entries)
 }
 
-fn gaussian_elimination(mut matrix: Matrix) -> Result<Solution> {
-    let mut pivot_row = matrix.first_row();
-    let mut pivot_col = matrix.first_col();
+/// Solves the sparse augmented matrix, treating any value within `tol` of zero as zero
+fn gaussian_elimination(mut matrix: CsrMatrix, tol: f64, interrupt: &Interrupt)
+    -> Result<SolveResult>
+{
+    let height = matrix.height();
+    let last_col = Col::new(matrix.width() - 1);
+    let mut pivot_row = 0;
+    let mut pivot_col = Col::new(0);
 
     info!("Performing gaussian elimination");
     debug!("{:?}", matrix);
 
     info!("Reducing matrix to triangular form");
-    while pivot_row.is_valid(&matrix) && pivot_col.is_valid(&matrix) {
-        let pivot_max = find_pivot_max(&matrix, pivot_row, pivot_col);
+    while pivot_row < height && pivot_col.index() < matrix.width() {
+        if let Some(status) = interrupt.check() {
+            info!("Stopping elimination: {:?}", status);
+            return Ok(SolveResult::new(status, None, SolveStats::default()));
+        }
+
+        let pivot_max = find_sparse_pivot_max(&matrix, Row::new(pivot_row), pivot_col);
 
-        if matrix.value(pivot_max, pivot_col) == 0.0 {
+        if matrix.value(pivot_max, pivot_col).abs() <= tol {
             pivot_col += 1;
         } else {
-            matrix.swap_rows(pivot_row, pivot_max);
+            matrix.swap_rows(Row::new(pivot_row), pivot_max);
 
-            for row in matrix.rows_from(pivot_row + 1) {
-                let coeff = matrix.value(row, pivot_col) / matrix.value(pivot_row, pivot_col);
-                matrix.set_value(row, pivot_col, 0.0);
+            // only rows with an entry in the pivot column need to change
+            let pivot_value = matrix.value(Row::new(pivot_row), pivot_col);
+            for row in (pivot_row + 1..height).map(Row::new) {
+                let value = matrix.value(row, pivot_col);
+                if value == 0.0 { continue; }
 
-                for col in matrix.cols_from(pivot_col + 1) {
-                    let value = matrix.value(row, col) - matrix.value(pivot_row, col) * coeff;
-                    matrix.set_value(row, col, value);
-                }
+                matrix.add_row(Row::new(pivot_row), row, -value / pivot_value);
+                matrix.set_value(row, pivot_col, 0.0);
             }
 
             pivot_row += 1;
@@ -93,17 +111,19 @@ fn gaussian_elimination(mut matrix: Matrix) -> Result<Solution> {
         debug!("Step");
         debug!("{:?}", matrix);
 
-        if matrix.has_zero_row() {
+        if has_zero_row(&matrix, tol) {
             return SolverError::underspecified("Two or more rows are linearly dependent.");
         }
     }
 
     info!("Back substituting");
-    let mut coeffs = vec![0.0; matrix.height()];
+    let mut coeffs = vec![0.0; height];
     for row in matrix.rows().rev() {
-        let mut coeff = matrix.value(row, matrix.last_col());
-        for col in matrix.cols_range(Col::from(row + 1), matrix.last_col()) {
-            coeff -= matrix.value(row, col) * coeffs[col.index()];
+        let mut coeff = matrix.value(row, last_col);
+        for (col, value) in matrix.row(row) {
+            if col.index() > row.index() && col < last_col {
+                coeff -= value * coeffs[col.index()];
+            }
         }
 
         coeffs[row.index()] = coeff / matrix.value(row, Col::from(row));
@@ -114,10 +134,23 @@ fn gaussian_elimination(mut matrix: Matrix) -> Result<Solution> {
 
     let solution = Solution::new(coeffs, None);
     info!("Solution found: {:?}", solution);
-    Ok(solution)
+    Ok(SolveResult::new(SolveStatus::Optimal, Some(solution), SolveStats::default()))
 }
 
-fn find_pivot_max(matrix: &Matrix, cur_pivot_row: Row, pivot_col: Col) -> Row {
+fn find_sparse_pivot_max(matrix: &CsrMatrix, cur_pivot_row: Row, pivot_col: Col) -> Row {
+    let mut max_value = 0.0;
+    let mut pivot_max = cur_pivot_row;
+    for row in (cur_pivot_row.index()..matrix.height()).map(Row::new) {
+        let cur_value = matrix.value(row, pivot_col).abs();
+        if cur_value > max_value {
+            max_value = cur_value;
+            pivot_max = row;
+        }
+    }
+    pivot_max
+}
+
+pub(crate) fn find_pivot_max(matrix: &Matrix, cur_pivot_row: Row, pivot_col: Col) -> Row {
     let mut max_value = 0.0;
     let mut pivot_max = cur_pivot_row;
     for row in matrix.rows_from(cur_pivot_row) {
@@ -129,3 +162,7 @@ fn find_pivot_max(matrix: &Matrix, cur_pivot_row: Row, pivot_col: Col) -> Row {
     }
     pivot_max
 }
+
+fn has_zero_row(matrix: &CsrMatrix, tol: f64) -> bool {
+    matrix.rows().any(|row| matrix.row(row).all(|(_, value)| value.abs() <= tol))
+}
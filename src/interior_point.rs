@@ -0,0 +1,739 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::f64;
+
+use log::{debug, info, warn};
+
+use crate::{ConstraintKind, Problem, Solution, ObjectiveKind, Result, SolverError, SolveResult,
+    SolveStatus, SolveStats, SolverOptions, Progress};
+use crate::options::Interrupt;
+use crate::simplex;
+
+/// The maximum number of iterations when the options do not set a limit
+const DEFAULT_ITERATION_LIMIT: u64 = 200;
+
+/// The fraction of the distance to the boundary of the positive orthant taken by each
+/// step
+const STEP_FRACTION: f64 = 0.995;
+
+/// The largest value kept in the smaller half of a free column split into two
+const SPLIT_LIMIT: f64 = 1.0;
+
+/// The number of rounds of iterative refinement for each solve of the normal equations
+const REFINEMENTS: usize = 2;
+
+/// Pivots of the normal equations smaller than this fraction of their diagonal entry
+/// are treated as zero, for rows that are linearly dependent on earlier rows
+const CHOLESKY_PIVOT: f64 = 1e-14;
+
+/// A column of the problem in standard form, where every column is non-negative.  It
+/// is `sign` times the original column `index`, after that is shifted by its offset,
+/// and is bounded above by `upper`, which may be infinite.
+#[derive(Debug, Clone, Copy)]
+struct Column {
+    index: usize,
+    sign: f64,
+    upper: f64,
+}
+
+impl Column {
+    fn is_bounded(&self) -> bool {
+        self.upper.is_finite()
+    }
+}
+
+/// The problem in the standard form `min c.x` subject to `A x = b` and `0 <= x <= u`.
+/// As in the simplex method, the original columns are the problem variables followed
+/// by one logical column per constraint, each constraint being written as `a.x + s = b`.
+/// Costs are always minimized; maximization problems have their costs negated.
+#[derive(Clone)]
+struct StandardForm {
+    /// The sparse original columns and their costs
+    original: Vec<Vec<(usize, f64)>>,
+    costs: Vec<f64>,
+
+    /// The value of each original column when its standard form columns are zero
+    offsets: Vec<f64>,
+
+    columns: Vec<Column>,
+    constants: Vec<f64>,
+
+    num_variables: usize,
+    objective_kind: ObjectiveKind,
+}
+
+impl StandardForm {
+    fn new(problem: &Problem, objective_kind: ObjectiveKind) -> StandardForm {
+        let num_variables = problem.num_variables();
+        let num_constraints = problem.num_constraints();
+        info!("Set up interior point problem with {} constraints and {} variables",
+              num_constraints, num_variables);
+
+        let mut original = vec![Vec::new(); num_variables + num_constraints];
+        let mut bounds: Vec<(f64, f64)> = problem.variables()
+            .map(|variable| problem.bounds(*variable)).collect();
+        let mut constants = Vec::with_capacity(num_constraints);
+        for (i, constraint) in problem.constraints().iter().enumerate() {
+            let mut terms: Vec<(usize, f64)> = constraint.expr().iter()
+                .filter(|(_, value)| **value != 0.0)
+                .map(|(index, value)| (*index as usize, *value)).collect();
+            terms.sort_by_key(|(index, _)| *index);
+            for (index, value) in terms {
+                original[index].push((i, value));
+            }
+
+            original[num_variables + i].push((i, 1.0));
+            bounds.push(match constraint.kind() {
+                ConstraintKind::LessThanOrEqualTo => (0.0, f64::INFINITY),
+                ConstraintKind::GreaterThanOrEqualTo => (f64::NEG_INFINITY, 0.0),
+                ConstraintKind::EqualTo => (0.0, 0.0),
+                ConstraintKind::Range(lower) => (0.0, constraint.constant() - lower),
+            });
+            constants.push(constraint.constant());
+        }
+
+        let mult = match objective_kind {
+            ObjectiveKind::Minimize => 1.0,
+            ObjectiveKind::Maximize => -1.0,
+        };
+        let mut costs = vec![0.0; original.len()];
+        if let Some(expr) = problem.objective_expr() {
+            for (index, value) in expr.iter() {
+                costs[*index as usize] = mult * value;
+            }
+        }
+
+        // fixed columns are moved into the constants, columns with a finite bound are
+        // shifted to start at zero, and free columns are split into two
+        let mut offsets = Vec::with_capacity(original.len());
+        let mut columns = Vec::new();
+        for (index, (lower, upper)) in bounds.into_iter().enumerate() {
+            let offset = if lower == upper {
+                lower
+            } else if lower.is_finite() {
+                columns.push(Column { index, sign: 1.0, upper: upper - lower });
+                lower
+            } else if upper.is_finite() {
+                columns.push(Column { index, sign: -1.0, upper: f64::INFINITY });
+                upper
+            } else {
+                columns.push(Column { index, sign: 1.0, upper: f64::INFINITY });
+                columns.push(Column { index, sign: -1.0, upper: f64::INFINITY });
+                0.0
+            };
+
+            for (row, value) in original[index].iter() {
+                constants[*row] -= value * offset;
+            }
+            offsets.push(offset);
+        }
+
+        StandardForm {
+            original,
+            costs,
+            offsets,
+            columns,
+            constants,
+            num_variables,
+            objective_kind,
+        }
+    }
+
+    /// The same problem with no objective, for checking that it is feasible
+    fn without_costs(&self) -> StandardForm {
+        StandardForm { costs: vec![0.0; self.costs.len()], ..self.clone() }
+    }
+
+    fn num_constraints(&self) -> usize {
+        self.constants.len()
+    }
+
+    fn entries(&self, column: &Column) -> impl Iterator<Item=(usize, f64)> + '_ {
+        let sign = column.sign;
+        self.original[column.index].iter().map(move |(row, value)| (*row, sign * value))
+    }
+
+    fn cost(&self, column: &Column) -> f64 {
+        column.sign * self.costs[column.index]
+    }
+
+    fn dot(&self, y: &[f64], column: &Column) -> f64 {
+        self.entries(column).map(|(row, value)| y[row] * value).sum()
+    }
+
+    /// Factorizes `A D A^T` for the diagonal matrix `D`
+    fn normal_equations(&self, diagonal: &[f64]) -> Cholesky {
+        let size = self.num_constraints();
+        let mut matrix = vec![0.0; size * size];
+        for (column, d) in self.columns.iter().zip(diagonal) {
+            for (row, value) in self.entries(column) {
+                for (other, other_value) in self.entries(column) {
+                    if other > row { continue; }
+                    matrix[row * size + other] += d * value * other_value;
+                }
+            }
+        }
+
+        Cholesky::new(matrix, size)
+    }
+
+    /// The values of the original columns for the standard form values `x`
+    fn original_values(&self, x: &[f64]) -> Vec<f64> {
+        let mut values = self.offsets.clone();
+        for (column, value) in self.columns.iter().zip(x) {
+            values[column.index] += column.sign * value;
+        }
+        values
+    }
+}
+
+/// A dense Cholesky factorization `L L^T` of a symmetric positive semi-definite matrix.
+/// Rows that are dependent on earlier rows are dropped, so that solving gives zero in
+/// those rows.
+struct Cholesky {
+    size: usize,
+    lower: Vec<f64>,
+}
+
+impl Cholesky {
+    /// Factorizes the matrix, of which only the lower triangle is used
+    fn new(mut lower: Vec<f64>, size: usize) -> Cholesky {
+        for j in 0..size {
+            let diagonal = lower[j * size + j];
+            let mut pivot = diagonal;
+            for k in 0..j {
+                pivot -= lower[j * size + k].powi(2);
+            }
+
+            if pivot <= CHOLESKY_PIVOT * diagonal {
+                debug!("Dropping dependent row {} of the normal equations", j);
+                lower[j * size + j] = f64::INFINITY;
+                for i in (j + 1)..size {
+                    lower[i * size + j] = 0.0;
+                }
+                continue;
+            }
+
+            let pivot = pivot.sqrt();
+            lower[j * size + j] = pivot;
+            for i in (j + 1)..size {
+                let mut value = lower[i * size + j];
+                for k in 0..j {
+                    value -= lower[i * size + k] * lower[j * size + k];
+                }
+                lower[i * size + j] = value / pivot;
+            }
+        }
+
+        Cholesky { size, lower }
+    }
+
+    fn solve(&self, mut rhs: Vec<f64>) -> Vec<f64> {
+        let size = self.size;
+        for i in 0..size {
+            let row = &self.lower[i * size..i * size + i];
+            let value = rhs[i] - row.iter().zip(rhs.iter()).map(|(l, x)| l * x).sum::<f64>();
+            rhs[i] = value / self.lower[i * size + i];
+        }
+
+        for i in (0..size).rev() {
+            let value = rhs[i] - rhs.iter().enumerate().skip(i + 1)
+                .map(|(k, x)| self.lower[k * size + i] * x).sum::<f64>();
+            rhs[i] = value / self.lower[i * size + i];
+        }
+
+        rhs
+    }
+}
+
+/// A primal-dual point.  `w` holds the slacks of the upper bounds, and `v` their duals,
+/// with both zero for columns without an upper bound.
+#[derive(Debug, Clone)]
+struct Point {
+    x: Vec<f64>,
+    w: Vec<f64>,
+    y: Vec<f64>,
+    z: Vec<f64>,
+    v: Vec<f64>,
+}
+
+/// The residuals of the primal and dual constraints, and of complementarity
+struct Residuals {
+    primal: Vec<f64>,
+    upper: Vec<f64>,
+    dual: Vec<f64>,
+    xz: Vec<f64>,
+    wv: Vec<f64>,
+}
+
+impl Point {
+    /// Finds a starting point from the least squares solutions of the primal and dual
+    /// constraints, moved into the interior
+    fn initial(form: &StandardForm) -> Point {
+        let ones = vec![1.0; form.columns.len()];
+        let normal = form.normal_equations(&ones);
+
+        let u = normal.solve(form.constants.clone());
+        let mut ac = vec![0.0; form.num_constraints()];
+        for column in form.columns.iter() {
+            let cost = form.cost(column);
+            for (row, value) in form.entries(column) {
+                ac[row] += value * cost;
+            }
+        }
+        let y = normal.solve(ac);
+
+        let mut point = Point {
+            x: Vec::new(),
+            w: Vec::new(),
+            y,
+            z: Vec::new(),
+            v: Vec::new(),
+        };
+
+        for column in form.columns.iter() {
+            let x = form.dot(&u, column).max(1.0);
+            let z = form.cost(column) - form.dot(&point.y, column);
+            if column.is_bounded() {
+                let x = x.min(column.upper / 2.0);
+                point.x.push(x);
+                point.w.push(column.upper - x);
+                point.z.push(z.max(0.0) + 1.0);
+                point.v.push((-z).max(0.0) + 1.0);
+            } else {
+                point.x.push(x);
+                point.w.push(0.0);
+                point.z.push(z.max(1.0));
+                point.v.push(0.0);
+            }
+        }
+
+        point
+    }
+
+    /// The average complementarity product
+    fn mu(&self, form: &StandardForm) -> f64 {
+        let mut total = 0.0;
+        let mut count = 0;
+        for (j, column) in form.columns.iter().enumerate() {
+            total += self.x[j] * self.z[j];
+            count += 1;
+            if column.is_bounded() {
+                total += self.w[j] * self.v[j];
+                count += 1;
+            }
+        }
+
+        if count == 0 { 0.0 } else { total / count as f64 }
+    }
+
+    fn residuals(&self, form: &StandardForm) -> Residuals {
+        let mut primal = form.constants.clone();
+        let mut upper = vec![0.0; form.columns.len()];
+        let mut dual = vec![0.0; form.columns.len()];
+        for (j, column) in form.columns.iter().enumerate() {
+            for (row, value) in form.entries(column) {
+                primal[row] -= value * self.x[j];
+            }
+
+            dual[j] = form.cost(column) - form.dot(&self.y, column) - self.z[j] + self.v[j];
+            if column.is_bounded() {
+                upper[j] = column.upper - self.x[j] - self.w[j];
+            }
+        }
+
+        let xz = self.x.iter().zip(self.z.iter()).map(|(x, z)| -x * z).collect();
+        let wv = self.w.iter().zip(self.v.iter()).map(|(w, v)| -w * v).collect();
+        Residuals { primal, upper, dual, xz, wv }
+    }
+
+    fn primal_objective(&self, form: &StandardForm) -> f64 {
+        form.columns.iter().zip(self.x.iter())
+            .map(|(column, x)| form.cost(column) * x).sum()
+    }
+
+    fn dual_objective(&self, form: &StandardForm) -> f64 {
+        let by: f64 = form.constants.iter().zip(self.y.iter()).map(|(b, y)| b * y).sum();
+        let uv: f64 = form.columns.iter().zip(self.v.iter())
+            .filter(|(column, _)| column.is_bounded())
+            .map(|(column, v)| column.upper * v).sum();
+        by - uv
+    }
+
+    /// Solves the Newton equations for the direction reducing the residuals, using the
+    /// factorization of the normal equations for this point
+    fn direction(
+        &self,
+        form: &StandardForm,
+        normal: &Cholesky,
+        theta: &[f64],
+        residuals: &Residuals,
+    ) -> Point {
+        let r = residuals;
+        let rho: Vec<f64> = form.columns.iter().enumerate().map(|(j, column)| {
+            let mut rho = r.dual[j] - r.xz[j] / self.x[j];
+            if column.is_bounded() {
+                rho += (r.wv[j] - self.v[j] * r.upper[j]) / self.w[j];
+            }
+            rho
+        }).collect();
+
+        let mut rhs = r.primal.clone();
+        for (j, column) in form.columns.iter().enumerate() {
+            for (row, value) in form.entries(column) {
+                rhs[row] += value * theta[j] * rho[j];
+            }
+        }
+        let mut dy = normal.solve(rhs);
+
+        // the normal equations lose accuracy as the iterates approach the boundary, so
+        // the solution is refined using the residual of `A dx = r_b`
+        for _ in 0..REFINEMENTS {
+            let mut error = r.primal.clone();
+            for (j, column) in form.columns.iter().enumerate() {
+                let dx = theta[j] * (form.dot(&dy, column) - rho[j]);
+                for (row, value) in form.entries(column) {
+                    error[row] -= value * dx;
+                }
+            }
+
+            let correction = normal.solve(error);
+            for (dy, correction) in dy.iter_mut().zip(correction) {
+                *dy += correction;
+            }
+        }
+
+        let mut direction = Point {
+            x: Vec::with_capacity(form.columns.len()),
+            w: Vec::with_capacity(form.columns.len()),
+            y: dy,
+            z: Vec::with_capacity(form.columns.len()),
+            v: Vec::with_capacity(form.columns.len()),
+        };
+
+        for (j, column) in form.columns.iter().enumerate() {
+            let dx = theta[j] * (form.dot(&direction.y, column) - rho[j]);
+            direction.x.push(dx);
+            direction.z.push((r.xz[j] - self.z[j] * dx) / self.x[j]);
+            if column.is_bounded() {
+                let dw = r.upper[j] - dx;
+                direction.w.push(dw);
+                direction.v.push((r.wv[j] - self.v[j] * dw) / self.w[j]);
+            } else {
+                direction.w.push(0.0);
+                direction.v.push(0.0);
+            }
+        }
+
+        direction
+    }
+
+    /// The largest primal and dual steps, up to one, keeping the point non-negative
+    fn max_steps(&self, direction: &Point) -> (f64, f64) {
+        let max_step = |values: &[f64], steps: &[f64], limit: f64| {
+            values.iter().zip(steps).filter(|(_, step)| **step < 0.0)
+                .map(|(value, step)| -value / step).fold(limit, f64::min)
+        };
+
+        let primal = max_step(&self.w, &direction.w, max_step(&self.x, &direction.x, 1.0));
+        let dual = max_step(&self.v, &direction.v, max_step(&self.z, &direction.z, 1.0));
+        (primal, dual)
+    }
+
+    fn step(&mut self, direction: &Point, primal: f64, dual: f64) {
+        let add = |values: &mut Vec<f64>, steps: &[f64], size: f64| {
+            for (value, step) in values.iter_mut().zip(steps) {
+                *value += size * step;
+            }
+        };
+
+        add(&mut self.x, &direction.x, primal);
+        add(&mut self.w, &direction.w, primal);
+        add(&mut self.y, &direction.y, dual);
+        add(&mut self.z, &direction.z, dual);
+        add(&mut self.v, &direction.v, dual);
+    }
+
+    /// Moves both halves of each split free column down by the same amount, so the
+    /// smaller is at most `SPLIT_LIMIT`.  This leaves the value of the free column alone,
+    /// but stops the halves growing together, which ruins the accuracy of the normal
+    /// equations.
+    fn shift_free_columns(&mut self, form: &StandardForm) {
+        for j in 1..form.columns.len() {
+            if form.columns[j].index != form.columns[j - 1].index { continue; }
+
+            let shift = self.x[j].min(self.x[j - 1]) - SPLIT_LIMIT;
+            if shift > 0.0 {
+                self.x[j] -= shift;
+                self.x[j - 1] -= shift;
+            }
+        }
+    }
+
+    /// The average complementarity product after taking the given steps
+    fn mu_after(&self, form: &StandardForm, direction: &Point, primal: f64, dual: f64) -> f64 {
+        let mut total = 0.0;
+        let mut count = 0;
+        for (j, column) in form.columns.iter().enumerate() {
+            total += (self.x[j] + primal * direction.x[j]) * (self.z[j] + dual * direction.z[j]);
+            count += 1;
+            if column.is_bounded() {
+                total += (self.w[j] + primal * direction.w[j]) *
+                    (self.v[j] + dual * direction.v[j]);
+                count += 1;
+            }
+        }
+
+        if count == 0 { 0.0 } else { total / count as f64 }
+    }
+
+    /// Whether the dual part of the point, scaled to have a dual objective of one,
+    /// nearly satisfies `A^T y + z - v = 0`.  That proves by Farkas' lemma that the
+    /// primal constraints have no solution, other than ones of enormous size.
+    fn is_dual_ray(&self, form: &StandardForm, residuals: &Residuals, tol: f64) -> bool {
+        let objective = self.dual_objective(form);
+        if objective <= 0.0 { return false; }
+
+        form.columns.iter().zip(residuals.dual.iter())
+            .all(|(column, residual)| (form.cost(column) - residual).abs() <= tol * objective)
+    }
+
+    /// Whether the primal part of the point, scaled to have an objective of minus one,
+    /// nearly satisfies `A x = 0` with the bounded columns nearly zero.  That is a
+    /// direction along which the objective decreases without limit.
+    fn is_primal_ray(&self, form: &StandardForm, residuals: &Residuals, tol: f64) -> bool {
+        let objective = -self.primal_objective(form);
+        if objective <= 0.0 { return false; }
+
+        let bounded = form.columns.iter().zip(self.x.iter())
+            .all(|(column, x)| !column.is_bounded() || *x <= tol * objective);
+        bounded && form.constants.iter().zip(residuals.primal.iter())
+            .all(|(constant, residual)| (constant - residual).abs() <= tol * objective)
+    }
+}
+
+fn max_norm(values: &[f64]) -> f64 {
+    values.iter().map(|value| value.abs()).fold(0.0, f64::max)
+}
+
+pub fn solve(
+    problem: &Problem,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
+    let objective_kind = match problem.objective_kind() {
+        None => {
+            return SolverError::invalid_objective("Must set an objective for interior point.");
+        }, Some(kind) => kind,
+    };
+
+    let form = StandardForm::new(problem, objective_kind);
+    let mut stats = SolveStats::default();
+    let (status, point) = interior_point(&form, &mut stats, options, interrupt)?;
+    if status == SolveStatus::Optimal && !options.skip_crossover {
+        let solution = produce_solution(problem, &form, &point, true);
+        return simplex::crossover(problem, solution, stats, options, interrupt);
+    }
+
+    // a point that is not optimal is still a usable solution if it is feasible
+    let residuals = point.residuals(&form);
+    let feasible = max_norm(&residuals.primal).max(max_norm(&residuals.upper)) <=
+        options.tolerances.feasibility;
+    let (status, solution) = match status {
+        SolveStatus::Optimal => (status, Some(produce_solution(problem, &form, &point, true))),
+        SolveStatus::Infeasible | SolveStatus::Unbounded => (status, None),
+        _ if feasible => {
+            info!("Stopped with a feasible point: {:?}", status);
+            (SolveStatus::Feasible, Some(produce_solution(problem, &form, &point, false)))
+        },
+        _ => (status, None),
+    };
+
+    Ok(SolveResult::new(status, solution, stats))
+}
+
+/// Runs the predictor-corrector method until the point is optimal, or the problem is
+/// shown to be infeasible or unbounded.  If there is no iteration limit in the options,
+/// at most `DEFAULT_ITERATION_LIMIT` iterations are run.
+fn interior_point(
+    form: &StandardForm,
+    stats: &mut SolveStats,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<(SolveStatus, Point)> {
+    let mut point = Point::initial(form);
+    let tolerances = options.tolerances;
+    let max_iterations = options.iteration_limit
+        .unwrap_or(stats.iterations + DEFAULT_ITERATION_LIMIT);
+
+    let status = loop {
+        let residuals = point.residuals(form);
+        let primal_infeasibility = max_norm(&residuals.primal).max(max_norm(&residuals.upper));
+        let dual_infeasibility = max_norm(&residuals.dual);
+        let primal_objective = point.primal_objective(form);
+        let gap = (primal_objective - point.dual_objective(form)).abs() /
+            (1.0 + primal_objective.abs());
+        info!("Iteration {}: primal infeasibility {:e}, dual infeasibility {:e}, gap {:e}",
+              stats.iterations, primal_infeasibility, dual_infeasibility, gap);
+
+        if primal_infeasibility <= tolerances.feasibility &&
+            dual_infeasibility <= tolerances.optimality && gap <= tolerances.optimality {
+            info!("Duality gap closed - solution is optimal");
+            break SolveStatus::Optimal;
+        }
+
+        if !primal_objective.is_finite() || !gap.is_finite() {
+            return SolverError::unable_to_solve("Interior point iterates are not finite.");
+        }
+
+        if primal_infeasibility > tolerances.feasibility &&
+            point.is_dual_ray(form, &residuals, tolerances.feasibility) {
+            info!("Dual iterates approach a ray.  Problem is infeasible.");
+            break SolveStatus::Infeasible;
+        }
+
+        // without a feasible point, the ray shows only that the dual is infeasible, so
+        // whether the problem is unbounded is settled by looking for any feasible point
+        if dual_infeasibility > tolerances.optimality &&
+            point.is_primal_ray(form, &residuals, tolerances.feasibility) {
+            if primal_infeasibility <= tolerances.feasibility {
+                info!("Primal iterates approach a ray.  Problem is unbounded.");
+                break SolveStatus::Unbounded;
+            }
+
+            info!("Primal iterates approach a ray.  Checking whether the problem is feasible.");
+            let (status, _) = interior_point(&form.without_costs(), stats, options, interrupt)?;
+            break match status {
+                SolveStatus::Optimal => SolveStatus::Unbounded,
+                status => status,
+            };
+        }
+
+        // once complementarity has vanished the remaining residuals cannot be reduced,
+        // which happens when dependent constraints are inconsistent
+        let mu = point.mu(form);
+        if mu <= tolerances.optimality.powi(2) * (1.0 + primal_objective.abs()) {
+            if primal_infeasibility > tolerances.feasibility {
+                info!("Stalled with primal residuals.  Problem is infeasible.");
+                break SolveStatus::Infeasible;
+            } else {
+                info!("Stalled with dual residuals.  Problem is unbounded.");
+                break SolveStatus::Unbounded;
+            }
+        }
+
+        if stats.iterations >= max_iterations {
+            warn!("Warning.  Failed to find solution after {} iterations", stats.iterations);
+            break SolveStatus::IterationLimit;
+        }
+
+        if let Some(status) = interrupt.check() {
+            warn!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            break status;
+        }
+
+        let theta: Vec<f64> = form.columns.iter().enumerate().map(|(j, column)| {
+            let mut inverse = point.z[j] / point.x[j];
+            if column.is_bounded() {
+                inverse += point.v[j] / point.w[j];
+            }
+            1.0 / inverse
+        }).collect();
+        let normal = form.normal_equations(&theta);
+
+        // the predictor step aims straight for the optimum, and its progress decides
+        // how much centering the corrector step uses
+        let affine = point.direction(form, &normal, &theta, &residuals);
+        let (primal, dual) = point.max_steps(&affine);
+        let sigma = if mu > 0.0 {
+            (point.mu_after(form, &affine, primal, dual) / mu).powi(3).min(1.0)
+        } else {
+            0.0
+        };
+
+        let mut corrector = residuals;
+        for j in 0..form.columns.len() {
+            corrector.xz[j] += sigma * mu - affine.x[j] * affine.z[j];
+            if form.columns[j].is_bounded() {
+                corrector.wv[j] += sigma * mu - affine.w[j] * affine.v[j];
+            }
+        }
+
+        let direction = point.direction(form, &normal, &theta, &corrector);
+        let (primal, dual) = point.max_steps(&direction);
+        point.step(&direction, STEP_FRACTION * primal, STEP_FRACTION * dual);
+        point.shift_free_columns(form);
+
+        stats.iterations += 1;
+        let progress = Progress::InteriorPoint {
+            iteration: stats.iterations,
+            objective: objective_value(form, point.primal_objective(form)),
+            gap,
+        };
+        if let Some(status) = interrupt.report(progress) {
+            info!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            break status;
+        }
+    };
+
+    Ok((status, point))
+}
+
+/// The objective of the original problem, given the minimized objective of the
+/// standard form problem
+fn objective_value(form: &StandardForm, objective: f64) -> f64 {
+    let offset: f64 = form.costs.iter().zip(form.offsets.iter())
+        .map(|(cost, offset)| cost * offset).sum();
+    match form.objective_kind {
+        ObjectiveKind::Minimize => objective + offset,
+        ObjectiveKind::Maximize => -(objective + offset),
+    }
+}
+
+/// Produces the solution at the point, with the dual values and reduced costs if
+/// `duals` is set
+fn produce_solution(
+    problem: &Problem,
+    form: &StandardForm,
+    point: &Point,
+    duals: bool,
+) -> Solution {
+    let values = form.original_values(&point.x);
+    let coeffs = values[..form.num_variables].to_vec();
+    let objective = problem.objective_expr().map(|expr| {
+        expr.iter().map(|(index, value)| value * coeffs[*index as usize]).sum()
+    });
+
+    let mut solution = Solution::new(coeffs, objective);
+    if duals {
+        let mult = match form.objective_kind {
+            ObjectiveKind::Minimize => 1.0,
+            ObjectiveKind::Maximize => -1.0,
+        };
+
+        let reduced_costs = (0..form.num_variables).map(|index| {
+            let priced: f64 = form.original[index].iter()
+                .map(|(row, value)| point.y[*row] * value).sum();
+            mult * (form.costs[index] - priced)
+        }).collect();
+        let duals = point.y.iter().map(|y| mult * y).collect();
+        solution.set_duals(duals, reduced_costs);
+    }
+
+    info!("Solution found {:?}", solution);
+    solution
+}
@@ -19,14 +19,29 @@ use std::error;
 
 pub mod matrix;
 pub mod problem;
+pub mod solve_result;
+pub mod sensitivity;
+pub mod verify;
+pub mod options;
+pub mod lu;
 
 mod gaussian_elimination;
 mod simplex;
+mod revised_simplex;
+mod dual_simplex;
+mod interior_point;
 mod brute;
 
-pub use crate::problem::{Variable, Constraint, ConstraintKind,
+pub use crate::problem::{Variable, Constraint, ConstraintKind, ConstraintRef,
     ObjectiveKind, Expression, Problem, Solution, VariableKind};
-pub use crate::matrix::{Matrix, Row, Col};
+pub use crate::matrix::{Matrix, MatrixView, CsrMatrix, CscMatrix, Row, Col};
+pub use crate::solve_result::{SolveResult, SolveStatus, SolveStats};
+pub use crate::sensitivity::{Sensitivity, Ranging};
+pub use crate::verify::VerifyReport;
+pub use crate::lu::LuDecomposition;
+pub use crate::options::{SolverOptions, Tolerances, Algorithm, PricingRule, Progress, ProgressAction,
+    ProgressCallback};
+pub use crate::simplex::Basis;
 
 pub type Result<T> = std::result::Result<T, SolverError>;
 
@@ -56,6 +71,10 @@ impl SolverError {
         SolverError::new(ErrorKind::Infeasible, message)
     }
 
+    pub fn unbounded<T, M: Into<String>>(message: M) -> Result<T> {
+        SolverError::new(ErrorKind::Unbounded, message)
+    }
+
     pub fn underspecified<T, M: Into<String>>(message: M) -> Result<T> {
         SolverError::new(ErrorKind::Underspecified, message)
     }
@@ -68,6 +87,14 @@ impl SolverError {
         SolverError::new(ErrorKind::InvalidSolution, message)
     }
 
+    pub fn invalid_variable<T, M: Into<String>>(message: M) -> Result<T> {
+        SolverError::new(ErrorKind::InvalidVariable, message)
+    }
+
+    pub fn invalid_matrix<T, M: Into<String>>(message: M) -> Result<T> {
+        SolverError::new(ErrorKind::InvalidMatrix, message)
+    }
+
     pub fn kind(&self) -> ErrorKind {
         self.kind
     }
@@ -82,9 +109,12 @@ pub enum ErrorKind {
     InvalidConstraint,
     InvalidObjective,
     Infeasible,
+    Unbounded,
     Underspecified,
     UnableToSolve,
     InvalidSolution,
+    InvalidVariable,
+    InvalidMatrix,
 }
 
 impl fmt::Display for ErrorKind {
@@ -0,0 +1,197 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use log::{debug, info};
+
+use crate::{Matrix, Row, Col, Result, SolverError};
+use crate::gaussian_elimination::find_pivot_max;
+
+/// The LU decomposition `PA = LU` of a square matrix `A`, computed by gaussian
+/// elimination with partial pivoting.  Once computed, it can be used to solve
+/// `Ax = b` for any number of right hand sides.  A singular matrix still has a
+/// decomposition, with a zero on the diagonal of `U`, but it cannot be used to solve.
+#[derive(Debug, Clone)]
+pub struct LuDecomposition {
+    /// `U` on and above the diagonal, and the multipliers of the unit lower
+    /// triangular `L` below it
+    lu: Matrix,
+
+    /// The row of `A` moved to each row of `PA`
+    permutation: Vec<usize>,
+
+    /// The determinant of `P`, negated by each row swap
+    sign: f64,
+}
+
+impl LuDecomposition {
+    /// Decomposes the current view of `matrix`, treating any pivot within `tol` of zero
+    /// as zero.  Returns an error if the matrix is not square.
+    pub fn new(matrix: &Matrix, tol: f64) -> Result<LuDecomposition> {
+        if matrix.width() != matrix.height() {
+            return SolverError::invalid_matrix(format!("LU decomposition requires a square \
+                matrix, got {} x {}", matrix.width(), matrix.height()));
+        }
+
+        info!("Performing LU decomposition of a {} x {} matrix", matrix.width(), matrix.height());
+        let mut lu = matrix.view(matrix.first_row(), matrix.first_col(), matrix.width(),
+                                 matrix.height())?.to_matrix();
+        let mut permutation: Vec<usize> = (0..lu.height()).collect();
+        let mut sign = 1.0;
+
+        for pivot in 0..lu.height() {
+            let (pivot_row, pivot_col) = (Row::new(pivot), Col::new(pivot));
+            let pivot_max = find_pivot_max(&lu, pivot_row, pivot_col);
+            if lu.value(pivot_max, pivot_col).abs() <= tol {
+                debug!("No pivot in column {}; the matrix is singular", pivot);
+                for row in lu.rows_from(pivot_row) {
+                    lu.set_value(row, pivot_col, 0.0);
+                }
+                continue;
+            }
+
+            if pivot_max != pivot_row {
+                lu.swap_rows(pivot_row, pivot_max);
+                permutation.swap(pivot, pivot_max.index());
+                sign = -sign;
+            }
+
+            for row in lu.rows_from(pivot_row + 1) {
+                let coeff = lu.value(row, pivot_col) / lu.value(pivot_row, pivot_col);
+                lu.set_value(row, pivot_col, coeff);
+
+                for col in lu.cols_from(pivot_col + 1) {
+                    let value = lu.value(row, col) - lu.value(pivot_row, col) * coeff;
+                    lu.set_value(row, col, value);
+                }
+            }
+        }
+
+        debug!("{:?}", lu);
+        Ok(LuDecomposition { lu, permutation, sign })
+    }
+
+    /// The number of rows and columns of the decomposed matrix
+    pub fn size(&self) -> usize {
+        self.lu.height()
+    }
+
+    /// The row of the original matrix moved to each row by pivoting
+    pub fn permutation(&self) -> &[usize] {
+        &self.permutation
+    }
+
+    /// The unit lower triangular factor `L`
+    pub fn lower(&self) -> Matrix {
+        let mut lower = Matrix::identity(self.size());
+        for row in self.lu.rows() {
+            for col in self.lu.cols_range(Col::new(0), Col::from(row)) {
+                lower.set_value(row, col, self.lu.value(row, col));
+            }
+        }
+        lower
+    }
+
+    /// The upper triangular factor `U`
+    pub fn upper(&self) -> Matrix {
+        let mut upper = Matrix::zeros(self.size(), self.size());
+        for row in self.lu.rows() {
+            for col in self.lu.cols_from(Col::from(row)) {
+                upper.set_value(row, col, self.lu.value(row, col));
+            }
+        }
+        upper
+    }
+
+    /// Whether the decomposed matrix is singular, in which case its determinant is zero
+    pub fn is_singular(&self) -> bool {
+        self.lu.rows().any(|row| self.lu.value(row, Col::from(row)) == 0.0)
+    }
+
+    fn check_singular(&self) -> Result<()> {
+        if self.is_singular() {
+            return SolverError::underspecified("Matrix is singular; two or more rows are \
+                linearly dependent.");
+        }
+        Ok(())
+    }
+
+    /// Solves `Ax = b`, returning `x`.  Returns an error if `b` does not have one value
+    /// per row or the matrix is singular.
+    pub fn solve(&self, b: &[f64]) -> Result<Vec<f64>> {
+        if b.len() != self.size() {
+            return SolverError::invalid_matrix(format!("Expected a right hand side of length \
+                {}, got {}", self.size(), b.len()));
+        }
+        self.check_singular()?;
+
+        Ok(self.substitute(b))
+    }
+
+    /// Solves `AX = B` for each column of the current view of `b`, returning `X`.
+    /// Returns an error if `b` does not have one row per row of the decomposition or the
+    /// matrix is singular.
+    pub fn solve_matrix(&self, b: &Matrix) -> Result<Matrix> {
+        if b.height() != self.size() {
+            return SolverError::invalid_matrix(format!("Expected a right hand side with {} \
+                rows, got {}", self.size(), b.height()));
+        }
+        self.check_singular()?;
+
+        Ok(self.substitute_columns(b))
+    }
+
+    /// Forward and back substitution for a right hand side of the correct length
+    fn substitute(&self, b: &[f64]) -> Vec<f64> {
+        let mut x: Vec<f64> = self.permutation.iter().map(|index| b[*index]).collect();
+        for row in self.lu.rows() {
+            for col in self.lu.cols_range(Col::new(0), Col::from(row)) {
+                x[row.index()] -= self.lu.value(row, col) * x[col.index()];
+            }
+        }
+
+        for row in self.lu.rows().rev() {
+            for col in self.lu.cols_from(Col::from(row) + 1) {
+                x[row.index()] -= self.lu.value(row, col) * x[col.index()];
+            }
+            x[row.index()] /= self.lu.value(row, Col::from(row));
+        }
+
+        x
+    }
+
+    fn substitute_columns(&self, b: &Matrix) -> Matrix {
+        let mut x = Matrix::zeros(b.width(), b.height());
+        for (index, col) in b.cols().enumerate() {
+            let rhs: Vec<f64> = b.rows().map(|row| b.value(row, col)).collect();
+            for (row, value) in self.substitute(&rhs).into_iter().enumerate() {
+                x.set_value(Row::new(row), Col::new(index), value);
+            }
+        }
+        x
+    }
+
+    /// The determinant of the decomposed matrix
+    pub fn determinant(&self) -> f64 {
+        let product: f64 = self.lu.rows().map(|row| self.lu.value(row, Col::from(row))).product();
+        self.sign * product
+    }
+
+    /// The inverse of the decomposed matrix.  Returns an error if the matrix is singular.
+    pub fn inverse(&self) -> Result<Matrix> {
+        self.check_singular()?;
+        Ok(self.substitute_columns(&Matrix::identity(self.size())))
+    }
+}
@@ -18,6 +18,12 @@ use std::{f64, fmt, ops};
 
 use log::{warn};
 
+use crate::{Result, SolverError};
+
+/// A dense, row-major matrix.  The matrix may be restricted to a sub-view with `sub`,
+/// in which case the `Row` and `Col` handles still index into the overall matrix, while
+/// the whole-matrix operations such as `transpose` and `matmul` act on the view alone.
+#[derive(Clone)]
 pub struct Matrix {
     start_x: usize,
     start_y: usize,
@@ -41,15 +47,18 @@ impl fmt::Debug for Matrix {
 }
 
 impl Matrix {
-    pub(crate) fn new(width: usize, height: usize, coeffs: Vec<f64>) -> Matrix {
+    /// Creates a matrix from its coefficients in row-major order.  Returns an error if
+    /// there are not exactly `width * height` coefficients.
+    pub fn new(width: usize, height: usize, coeffs: Vec<f64>) -> Result<Matrix> {
         if width * height != coeffs.len() {
-            panic!();
+            return SolverError::invalid_matrix(format!("Expected {} coefficients for a {} x {} \
+                matrix, got {}", width * height, width, height, coeffs.len()));
         }
 
-        if width <= 2 || height <= 2 {
-            panic!();
-        }
+        Ok(Matrix::from_coeffs(width, height, coeffs))
+    }
 
+    fn from_coeffs(width: usize, height: usize, coeffs: Vec<f64>) -> Matrix {
         Matrix {
             start_x: 0,
             start_y: 0,
@@ -61,74 +70,217 @@ impl Matrix {
         }
     }
 
+    /// Creates a matrix from `(row, col, value)` entries given in any order, with every
+    /// other entry zero.  Values for the same entry are summed.  Returns an error if an
+    /// entry is outside the matrix.
+    pub fn from_entries<I>(width: usize, height: usize, entries: I) -> Result<Matrix>
+        where I: IntoIterator<Item=(usize, usize, f64)>
+    {
+        let mut matrix = Matrix::zeros(width, height);
+        for (row, col, value) in check_entries(width, height, entries)? {
+            matrix.coeffs[col + row * width] += value;
+        }
+        Ok(matrix)
+    }
+
+    /// Creates a matrix with every entry zero
+    pub fn zeros(width: usize, height: usize) -> Matrix {
+        Matrix::from_coeffs(width, height, vec![0.0; width * height])
+    }
+
+    /// Creates the square identity matrix
+    pub fn identity(size: usize) -> Matrix {
+        let mut matrix = Matrix::zeros(size, size);
+        for i in 0..size {
+            matrix.coeffs[i + i * size] = 1.0;
+        }
+        matrix
+    }
+
     /// Sets this matrix to be a sub-view into the overall matrix with the specified
-    /// `start_x` and `start_y` coords
-    pub fn sub(&mut self, start_x: usize, start_y: usize) {
-        if self.start_x >= self.end_x || self.start_y >= self.end_y {
-            panic!();
+    /// `start_x` and `start_y` coords.  Returns an error if the coords are past the end
+    /// of the matrix.
+    pub fn sub(&mut self, start_x: usize, start_y: usize) -> Result<()> {
+        if start_x > self.end_x || start_y > self.end_y {
+            return SolverError::invalid_matrix(format!("Sub-view at ({}, {}) is outside the {} \
+                x {} matrix", start_x, start_y, self.end_x, self.end_y));
         }
         self.start_x = start_x;
         self.start_y = start_y;
+        Ok(())
+    }
+
+    /// Returns a view of `height` rows and `width` columns of this matrix, starting at
+    /// `row` and `col`.  Returns an error if the view does not fit within the matrix.
+    pub fn view(&self, row: Row, col: Col, width: usize, height: usize) -> Result<MatrixView<'_>> {
+        if row.value < self.start_y || col.value < self.start_x ||
+            row.value + height > self.end_y || col.value + width > self.end_x {
+            return SolverError::invalid_matrix(format!("A {} x {} view at ({}, {}) is outside \
+                the matrix", width, height, col.value, row.value));
+        }
+
+        Ok(MatrixView { matrix: self, start_x: col.value, start_y: row.value, width, height })
+    }
+
+    /// The value at the given row and column, counted from the start of the view
+    fn get(&self, row: usize, col: usize) -> f64 {
+        self.coeffs[self.start_x + col + (self.start_y + row) * self.total_width]
     }
 
+    /// Creates a new matrix from the values of the current view, in row-major order
+    fn map_values<F: Fn(usize, usize) -> f64>(&self, width: usize, height: usize, f: F) -> Matrix {
+        let coeffs = (0..height).flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(|(row, col)| f(row, col)).collect();
+        Matrix::from_coeffs(width, height, coeffs)
+    }
+
+    fn check_same_size(&self, other: &Matrix) -> Result<()> {
+        if self.width() != other.width() || self.height() != other.height() {
+            return SolverError::invalid_matrix(format!("Matrix sizes {} x {} and {} x {} do \
+                not match", self.width(), self.height(), other.width(), other.height()));
+        }
+        Ok(())
+    }
+
+    /// Returns the transpose of the current view
+    pub fn transpose(&self) -> Matrix {
+        self.map_values(self.height(), self.width(), |row, col| self.get(col, row))
+    }
+
+    /// Multiplies the current view by the vector `x`, which must have one value per
+    /// column
+    pub fn mul_vec(&self, x: &[f64]) -> Result<Vec<f64>> {
+        if x.len() != self.width() {
+            return SolverError::invalid_matrix(format!("Expected a vector of length {}, got {}",
+                self.width(), x.len()));
+        }
+
+        Ok((0..self.height()).map(|row| {
+            x.iter().enumerate().map(|(col, value)| self.get(row, col) * value).sum()
+        }).collect())
+    }
+
+    /// Returns the matrix product of the current views of `self` and `other`
+    pub fn matmul(&self, other: &Matrix) -> Result<Matrix> {
+        if self.width() != other.height() {
+            return SolverError::invalid_matrix(format!("Cannot multiply a {} x {} matrix by a \
+                {} x {} matrix", self.width(), self.height(), other.width(), other.height()));
+        }
+
+        Ok(self.map_values(other.width(), self.height(), |row, col| {
+            (0..self.width()).map(|k| self.get(row, k) * other.get(k, col)).sum()
+        }))
+    }
+
+    /// Adds the current views of the matrices element by element
+    pub fn add(&self, other: &Matrix) -> Result<Matrix> {
+        self.check_same_size(other)?;
+        Ok(self.map_values(self.width(), self.height(), |row, col| {
+            self.get(row, col) + other.get(row, col)
+        }))
+    }
+
+    /// Subtracts the current view of `other` from this one element by element
+    pub fn subtract(&self, other: &Matrix) -> Result<Matrix> {
+        self.check_same_size(other)?;
+        Ok(self.map_values(self.width(), self.height(), |row, col| {
+            self.get(row, col) - other.get(row, col)
+        }))
+    }
+
+    /// Multiplies the current views of the matrices element by element
+    pub fn hadamard(&self, other: &Matrix) -> Result<Matrix> {
+        self.check_same_size(other)?;
+        Ok(self.map_values(self.width(), self.height(), |row, col| {
+            self.get(row, col) * other.get(row, col)
+        }))
+    }
+
+    /// Multiplies every element of the current view by `mult`
+    pub fn scale(&self, mult: f64) -> Matrix {
+        self.map(|value| value * mult)
+    }
+
+    /// Applies `f` to every element of the current view
+    pub fn map<F: Fn(f64) -> f64>(&self, f: F) -> Matrix {
+        self.map_values(self.width(), self.height(), |row, col| f(self.get(row, col)))
+    }
+
+    /// The handle of the last row of the view
     pub fn last_row(&self) -> Row {
         Row::new(self.end_y - 1)
     }
 
+    /// The handle of the last column of the view
     pub fn last_col(&self) -> Col {
         Col::new(self.end_x - 1)
     }
 
+    /// The handle of the first column of the view
     pub fn first_col(&self) -> Col {
         Col::new(self.start_x)
     }
 
+    /// The handle of the first row of the view
     pub fn first_row(&self) -> Row {
         Row::new(self.start_y)
     }
 
     pub fn cols_range(&self, start: Col, end: Col) -> impl DoubleEndedIterator<Item=Col> {
-        ops::Range { start: start.value, end: end.value }.map(|val| Col::new(val))
+        ops::Range { start: start.value, end: end.value }.map(Col::new)
     }
 
     pub fn cols_from(&self, start: Col) -> impl DoubleEndedIterator<Item=Col> {
-        ops::Range { start: start.value, end: self.end_x }.map(|val| Col::new(val))
+        ops::Range { start: start.value, end: self.end_x }.map(Col::new)
     }
 
     pub fn rows_range(&self, start: Col, end: Col) -> impl DoubleEndedIterator<Item=Row> {
-        ops::Range { start: start.value, end: end.value }.map(|val| Row::new(val))
+        ops::Range { start: start.value, end: end.value }.map(Row::new)
     }
 
     pub fn rows_from(&self, start: Row) -> impl DoubleEndedIterator<Item=Row> {
-        ops::Range { start: start.value, end: self.end_y }.map(|val| Row::new(val))
+        ops::Range { start: start.value, end: self.end_y }.map(Row::new)
     }
 
+    /// The handles of the columns of the view
     pub fn cols(&self) -> impl DoubleEndedIterator<Item=Col> {
-        ops::Range { start: self.start_x, end: self.end_x }.map(|val| Col::new(val))
+        ops::Range { start: self.start_x, end: self.end_x }.map(Col::new)
     }
 
+    /// The handles of the rows of the view
     pub fn rows(&self) -> impl DoubleEndedIterator<Item=Row> {
-         ops::Range { start: self.start_y, end: self.end_y }.map(|val| Row::new(val))
+         ops::Range { start: self.start_y, end: self.end_y }.map(Row::new)
     }
 
     pub fn width(&self) -> usize { self.end_x - self.start_x }
 
     pub fn height(&self) -> usize { self.end_y - self.start_y }
 
-    pub fn value(&self, row: Row, col: Col) -> f64 {
-        self.coeffs[col.value + row.value * self.total_width]
+    /// The position of the `row`, `col` entry in `coeffs`.  Panics if the entry is outside
+    /// the current view.
+    fn index(&self, row: Row, col: Col) -> usize {
+        if row.value < self.start_y || row.value >= self.end_y ||
+            col.value < self.start_x || col.value >= self.end_x {
+            panic!("Entry ({}, {}) is outside the view of rows {}..{} and columns {}..{}",
+                row.value, col.value, self.start_y, self.end_y, self.start_x, self.end_x);
+        }
+        col.value + row.value * self.total_width
     }
 
-    /// Only for use in matrix initialization
-    pub(crate) fn set_value_raw(&mut self, row: usize, col: usize, value: f64) {
-        self.coeffs[col + row * self.total_width] = value;
+    /// The value of the specified row, col entry.  `row` and `col` are handles into the
+    /// overall matrix, not counted from the start of the view.  Panics if the entry is
+    /// outside the current view.
+    pub fn value(&self, row: Row, col: Col) -> f64 {
+        self.coeffs[self.index(row, col)]
     }
 
-    /// Sets the specified row, col entry of the matrix to the value.  By careful using
-    /// this to solve systems, it is better to use one of the basic row matrix operations
-    /// below
+    /// Sets the specified row, col entry of the matrix to the value.  `row` and `col` are
+    /// handles into the overall matrix, as for `value`, and it panics if the entry is
+    /// outside the current view.  By careful using this to solve systems, it is better to
+    /// use one of the basic row matrix operations below
     pub fn set_value(&mut self, row: Row, col: Col, value: f64) {
-        self.coeffs[col.value + row.value * self.total_width] = value;
+        let index = self.index(row, col);
+        self.coeffs[index] = value;
     }
 
     fn swap_values(&mut self, row1: Row, col1: Col, row2: Row, col2: Col) {
@@ -187,13 +339,61 @@ impl Matrix {
     }
 }
 
+/// A borrowed rectangular view into a `Matrix`, created with `Matrix::view`.  Rows and
+/// columns of the view are counted from zero.
+#[derive(Clone, Copy)]
+pub struct MatrixView<'a> {
+    matrix: &'a Matrix,
+    start_x: usize,
+    start_y: usize,
+    width: usize,
+    height: usize,
+}
+
+impl fmt::Debug for MatrixView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.to_matrix())
+    }
+}
+
+impl MatrixView<'_> {
+    pub fn width(&self) -> usize { self.width }
+
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item=Row> {
+        (0..self.height).map(Row::new)
+    }
+
+    pub fn cols(&self) -> impl DoubleEndedIterator<Item=Col> {
+        (0..self.width).map(Col::new)
+    }
+
+    /// The value of the specified row, col entry, counted from the start of the view.
+    /// Panics if the entry is outside the view.
+    pub fn value(&self, row: Row, col: Col) -> f64 {
+        if row.value >= self.height || col.value >= self.width {
+            panic!("Entry ({}, {}) is outside the {} x {} view", row.value, col.value,
+                self.width, self.height);
+        }
+        self.matrix.value(Row::new(self.start_y + row.value), Col::new(self.start_x + col.value))
+    }
+
+    /// Copies the values of this view into a new matrix
+    pub fn to_matrix(&self) -> Matrix {
+        let coeffs = self.rows().flat_map(|row| self.cols().map(move |col| (row, col)))
+            .map(|(row, col)| self.value(row, col)).collect();
+        Matrix::from_coeffs(self.width, self.height, coeffs)
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct Row {
     value: usize,
 }
 
 impl Row {
-    pub(crate) fn new(value: usize) -> Row {
+    pub fn new(value: usize) -> Row {
         Row { value }
     }
 
@@ -262,7 +462,7 @@ pub struct Col {
 }
 
 impl Col {
-    pub(crate) fn new(value: usize) -> Col {
+    pub fn new(value: usize) -> Col {
         Col { value }
     }
 
@@ -324,3 +524,370 @@ impl ops::SubAssign<usize> for Col {
         self.value -= rhs;
     }
 }
+
+/// Compressed sparse storage shared by `CsrMatrix` and `CscMatrix`.  Entries are
+/// grouped into major lanes (rows for CSR, columns for CSC), and the entries of each
+/// lane are sorted by their minor index.  Zeros are never stored.
+#[derive(Debug, Clone, PartialEq)]
+struct Compressed {
+    major: usize,
+    minor: usize,
+    starts: Vec<usize>,
+    indices: Vec<usize>,
+    values: Vec<f64>,
+}
+
+impl Compressed {
+    fn new(major: usize, minor: usize) -> Compressed {
+        Compressed {
+            major,
+            minor,
+            starts: vec![0; major + 1],
+            indices: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Builds the storage from `(major, minor, value)` entries in any order, summing
+    /// duplicates
+    fn from_entries<I: Iterator<Item=(usize, usize, f64)>>(
+        major: usize,
+        minor: usize,
+        entries: I,
+    ) -> Compressed {
+        let mut entries: Vec<(usize, usize, f64)> = entries.collect();
+        entries.sort_by_key(|(a, b, _)| (*a, *b));
+
+        let mut summed: Vec<(usize, usize, f64)> = Vec::with_capacity(entries.len());
+        for (a, b, value) in entries {
+            match summed.last_mut() {
+                Some(last) if last.0 == a && last.1 == b => last.2 += value,
+                _ => summed.push((a, b, value)),
+            }
+        }
+
+        let mut compressed = Compressed::new(major, minor);
+        for (a, b, value) in summed {
+            if value == 0.0 { continue; }
+            compressed.starts[a + 1] += 1;
+            compressed.indices.push(b);
+            compressed.values.push(value);
+        }
+        for a in 0..major {
+            compressed.starts[a + 1] += compressed.starts[a];
+        }
+        compressed
+    }
+
+    fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    fn lane(&self, major: usize) -> impl Iterator<Item=(usize, f64)> + '_ {
+        let range = self.starts[major]..self.starts[major + 1];
+        self.indices[range.clone()].iter().cloned().zip(self.values[range].iter().cloned())
+    }
+
+    /// Returns the position of the entry in the storage, or where it would be inserted
+    fn find(&self, major: usize, minor: usize) -> std::result::Result<usize, usize> {
+        let start = self.starts[major];
+        self.indices[start..self.starts[major + 1]].binary_search(&minor)
+            .map(|pos| pos + start).map_err(|pos| pos + start)
+    }
+
+    fn get(&self, major: usize, minor: usize) -> f64 {
+        match self.find(major, minor) {
+            Ok(pos) => self.values[pos],
+            Err(_) => 0.0,
+        }
+    }
+
+    fn set(&mut self, major: usize, minor: usize, value: f64) {
+        match self.find(major, minor) {
+            Ok(pos) if value == 0.0 => {
+                self.indices.remove(pos);
+                self.values.remove(pos);
+                self.starts[major + 1..].iter_mut().for_each(|start| *start -= 1);
+            },
+            Ok(pos) => self.values[pos] = value,
+            Err(_) if value == 0.0 => (),
+            Err(pos) => {
+                self.indices.insert(pos, minor);
+                self.values.insert(pos, value);
+                self.starts[major + 1..].iter_mut().for_each(|start| *start += 1);
+            },
+        }
+    }
+
+    /// Replaces the entries of a lane with the sorted, non-zero `entries`
+    fn replace_lane(&mut self, major: usize, entries: Vec<(usize, f64)>) {
+        let range = self.starts[major]..self.starts[major + 1];
+        let old_len = range.len();
+        let new_len = entries.len();
+        self.indices.splice(range.clone(), entries.iter().map(|(index, _)| *index));
+        self.values.splice(range, entries.iter().map(|(_, value)| *value));
+
+        for start in self.starts[major + 1..].iter_mut() {
+            *start = *start + new_len - old_len;
+        }
+    }
+
+    fn swap_lanes(&mut self, a: usize, b: usize) {
+        if a == b { return; }
+        let lane_a: Vec<(usize, f64)> = self.lane(a).collect();
+        let lane_b: Vec<(usize, f64)> = self.lane(b).collect();
+        self.replace_lane(a, lane_b);
+        self.replace_lane(b, lane_a);
+    }
+
+    fn scale_lane(&mut self, major: usize, mult: f64) {
+        if mult == 0.0 {
+            self.replace_lane(major, Vec::new());
+            return;
+        }
+
+        let range = self.starts[major]..self.starts[major + 1];
+        self.values[range].iter_mut().for_each(|value| *value *= mult);
+    }
+
+    /// Adds lane `src` multiplied by `mult` to lane `dest`, merging the sorted entries
+    fn add_lane(&mut self, src: usize, dest: usize, mult: f64) {
+        let merged = {
+            let mut merged = Vec::new();
+            let mut src_iter = self.lane(src)
+                .map(|(index, value)| (index, value * mult)).peekable();
+            let mut dest_iter = self.lane(dest).peekable();
+            loop {
+                let entry = match (src_iter.peek(), dest_iter.peek()) {
+                    (None, None) => break,
+                    (Some(_), None) => src_iter.next().unwrap(),
+                    (None, Some(_)) => dest_iter.next().unwrap(),
+                    (Some((a, _)), Some((b, _))) if a < b => src_iter.next().unwrap(),
+                    (Some((a, _)), Some((b, _))) if a > b => dest_iter.next().unwrap(),
+                    (Some(_), Some(_)) => {
+                        let (index, value) = src_iter.next().unwrap();
+                        (index, value + dest_iter.next().unwrap().1)
+                    },
+                };
+                if entry.1 != 0.0 {
+                    merged.push(entry);
+                }
+            }
+            merged
+        };
+        self.replace_lane(dest, merged);
+    }
+
+    /// Returns the same entries with the major and minor lanes exchanged
+    fn transpose(&self) -> Compressed {
+        let mut starts = vec![0; self.minor + 1];
+        for index in self.indices.iter() {
+            starts[index + 1] += 1;
+        }
+        for b in 0..self.minor {
+            starts[b + 1] += starts[b];
+        }
+
+        let mut next = starts.clone();
+        let mut indices = vec![0; self.nnz()];
+        let mut values = vec![0.0; self.nnz()];
+        for a in 0..self.major {
+            for (b, value) in self.lane(a) {
+                indices[next[b]] = a;
+                values[next[b]] = value;
+                next[b] += 1;
+            }
+        }
+
+        Compressed { major: self.minor, minor: self.major, starts, indices, values }
+    }
+}
+
+/// A sparse matrix in compressed sparse row (CSR) form, storing only the non-zero
+/// entries of each row.  Iterating over a row and the basic row operations only
+/// touch the stored entries.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrMatrix {
+    data: Compressed,
+}
+
+impl CsrMatrix {
+    /// Creates a matrix of the given size with every entry zero
+    pub fn new(width: usize, height: usize) -> CsrMatrix {
+        CsrMatrix { data: Compressed::new(height, width) }
+    }
+
+    /// Creates a matrix from `(row, col, value)` entries given in any order.  Values
+    /// for the same entry are summed.  Returns an error if an entry is outside the matrix.
+    pub fn from_entries<I>(width: usize, height: usize, entries: I) -> Result<CsrMatrix>
+        where I: IntoIterator<Item=(usize, usize, f64)>
+    {
+        let entries = check_entries(width, height, entries)?;
+        Ok(CsrMatrix { data: Compressed::from_entries(height, width, entries.into_iter()) })
+    }
+
+    /// Creates a sparse copy of the current view of `matrix`
+    pub fn from_dense(matrix: &Matrix) -> CsrMatrix {
+        let data = Compressed::from_entries(matrix.height(), matrix.width(),
+                                            dense_entries(matrix));
+        CsrMatrix { data }
+    }
+
+    pub fn to_dense(&self) -> Matrix {
+        let mut coeffs = vec![0.0; self.width() * self.height()];
+        for row in 0..self.height() {
+            for (col, value) in self.data.lane(row) {
+                coeffs[col + row * self.width()] = value;
+            }
+        }
+        Matrix::from_coeffs(self.width(), self.height(), coeffs)
+    }
+
+    pub fn to_csc(&self) -> CscMatrix {
+        CscMatrix { data: self.data.transpose() }
+    }
+
+    pub fn width(&self) -> usize { self.data.minor }
+
+    pub fn height(&self) -> usize { self.data.major }
+
+    /// The number of stored, non-zero entries
+    pub fn nnz(&self) -> usize { self.data.nnz() }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item=Row> {
+        (0..self.height()).map(Row::new)
+    }
+
+    pub fn cols(&self) -> impl DoubleEndedIterator<Item=Col> {
+        (0..self.width()).map(Col::new)
+    }
+
+    /// Iterates over the non-zero entries of the row, in column order
+    pub fn row(&self, row: Row) -> impl Iterator<Item=(Col, f64)> + '_ {
+        self.data.lane(row.value).map(|(col, value)| (Col::new(col), value))
+    }
+
+    pub fn value(&self, row: Row, col: Col) -> f64 {
+        self.data.get(row.value, col.value)
+    }
+
+    /// Sets the specified row, col entry of the matrix to the value, removing the
+    /// entry if the value is zero
+    pub fn set_value(&mut self, row: Row, col: Col, value: f64) {
+        self.data.set(row.value, col.value, value);
+    }
+
+    /// One of the 3 basic matrix row operations - swaps row1 and row2
+    pub fn swap_rows(&mut self, row1: Row, row2: Row) {
+        self.data.swap_lanes(row1.value, row2.value);
+    }
+
+    /// One of the 3 basic matrix row operations - multiplies a row by a constant
+    pub fn multiply_row(&mut self, row: Row, mult: f64) {
+        if mult == 0.0 {
+            warn!("Multiplying row by 0 which is an invalid operation");
+        }
+
+        self.data.scale_lane(row.value, mult);
+    }
+
+    /// One of the 3 basic matrix row operations.  Adds `src` multiplied
+    /// by `mult` to `dest`
+    pub fn add_row(&mut self, src: Row, dest: Row, mult: f64) {
+        if mult == 0.0 {
+            warn!("Multiplying row by 0 which is an invalid operation");
+        }
+
+        self.data.add_lane(src.value, dest.value, mult);
+    }
+}
+
+/// A sparse matrix in compressed sparse column (CSC) form, storing only the non-zero
+/// entries of each column.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CscMatrix {
+    data: Compressed,
+}
+
+impl CscMatrix {
+    /// Creates a matrix of the given size with every entry zero
+    pub fn new(width: usize, height: usize) -> CscMatrix {
+        CscMatrix { data: Compressed::new(width, height) }
+    }
+
+    /// Creates a matrix from `(row, col, value)` entries given in any order.  Values
+    /// for the same entry are summed.  Returns an error if an entry is outside the matrix.
+    pub fn from_entries<I>(width: usize, height: usize, entries: I) -> Result<CscMatrix>
+        where I: IntoIterator<Item=(usize, usize, f64)>
+    {
+        let entries = check_entries(width, height, entries)?;
+        let entries = entries.into_iter().map(|(row, col, value)| (col, row, value));
+        Ok(CscMatrix { data: Compressed::from_entries(width, height, entries) })
+    }
+
+    /// Creates a sparse copy of the current view of `matrix`
+    pub fn from_dense(matrix: &Matrix) -> CscMatrix {
+        let entries = dense_entries(matrix).map(|(row, col, value)| (col, row, value));
+        CscMatrix { data: Compressed::from_entries(matrix.width(), matrix.height(), entries) }
+    }
+
+    pub fn to_dense(&self) -> Matrix {
+        self.to_csr().to_dense()
+    }
+
+    pub fn to_csr(&self) -> CsrMatrix {
+        CsrMatrix { data: self.data.transpose() }
+    }
+
+    pub fn width(&self) -> usize { self.data.major }
+
+    pub fn height(&self) -> usize { self.data.minor }
+
+    /// The number of stored, non-zero entries
+    pub fn nnz(&self) -> usize { self.data.nnz() }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item=Row> {
+        (0..self.height()).map(Row::new)
+    }
+
+    pub fn cols(&self) -> impl DoubleEndedIterator<Item=Col> {
+        (0..self.width()).map(Col::new)
+    }
+
+    /// Iterates over the non-zero entries of the column, in row order
+    pub fn col(&self, col: Col) -> impl Iterator<Item=(Row, f64)> + '_ {
+        self.data.lane(col.value).map(|(row, value)| (Row::new(row), value))
+    }
+
+    pub fn value(&self, row: Row, col: Col) -> f64 {
+        self.data.get(col.value, row.value)
+    }
+
+    /// Sets the specified row, col entry of the matrix to the value, removing the
+    /// entry if the value is zero
+    pub fn set_value(&mut self, row: Row, col: Col, value: f64) {
+        self.data.set(col.value, row.value, value);
+    }
+}
+
+/// The non-zero `(row, col, value)` entries of the current view of `matrix`, indexed
+/// from the start of the view
+fn dense_entries(matrix: &Matrix) -> impl Iterator<Item=(usize, usize, f64)> + '_ {
+    matrix.rows().flat_map(move |row| matrix.cols().map(move |col| (row, col)))
+        .map(move |(row, col)| (row.value - matrix.start_y, col.value - matrix.start_x,
+            matrix.value(row, col)))
+        .filter(|(_, _, value)| *value != 0.0)
+}
+
+fn check_entries<I>(width: usize, height: usize, entries: I) -> Result<Vec<(usize, usize, f64)>>
+    where I: IntoIterator<Item=(usize, usize, f64)>
+{
+    let entries: Vec<(usize, usize, f64)> = entries.into_iter().collect();
+    for (row, col, _) in entries.iter() {
+        if *row >= height || *col >= width {
+            return SolverError::invalid_matrix(format!("Entry ({}, {}) is outside the {} x {} \
+                matrix", row, col, width, height));
+        }
+    }
+    Ok(entries)
+}
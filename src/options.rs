@@ -0,0 +1,248 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::{SolveStatus, Basis};
+
+/// The tolerances used by the solvers, and when checking a solution against a problem
+#[derive(Debug, Clone, Copy)]
+pub struct Tolerances {
+    /// The amount by which a constraint or bound may be violated
+    pub feasibility: f64,
+
+    /// The amount by which a reduced cost may be of the wrong sign in an optimal
+    /// solution
+    pub optimality: f64,
+
+    /// The smallest magnitude accepted for a pivot element.  Smaller values are treated
+    /// as zero.
+    pub pivot: f64,
+
+    /// The distance from the nearest integer allowed for an integer variable
+    pub integrality: f64,
+}
+
+impl Default for Tolerances {
+    fn default() -> Tolerances {
+        Tolerances {
+            feasibility: 1e-6,
+            optimality: 1e-6,
+            pivot: 1e-9,
+            integrality: 1e-6,
+        }
+    }
+}
+
+/// The algorithms available to solve a problem
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Algorithm {
+    /// The bounded variable simplex method.  Integer variables are relaxed to
+    /// continuous variables within their bounds.
+    Simplex,
+
+    /// The bounded variable simplex method working from a factorization of the basis,
+    /// rather than the full tableau.  This is much faster and smaller on large, sparse
+    /// problems.  Integer variables are relaxed as with `Simplex`.
+    RevisedSimplex,
+
+    /// The dual simplex method, working from a factorization of the basis.  This
+    /// starts from a basis that is optimal but not feasible, which makes it the method
+    /// of choice for solving again after constraints are tightened or added, see
+    /// `SolverOptions::basis`.  If the starting basis is not optimal, the revised simplex
+    /// method is used instead.
+    DualSimplex,
+
+    /// A primal-dual interior point method with Mehrotra's predictor-corrector steps.
+    /// This converges in few iterations on large, dense problems, and stops once the
+    /// duality gap and the residuals are within the optimality and feasibility
+    /// tolerances.  The optimal point is then moved to an optimal basic solution with a
+    /// few simplex pivots, unless `skip_crossover` is set.
+    InteriorPoint,
+
+    /// Checks every assignment of the integer variables, solving for any continuous
+    /// variables with the simplex method
+    BruteForce,
+
+    /// Solves a square system of equality constraints, without an objective
+    GaussianElimination,
+}
+
+/// The rules for choosing the entering column in each simplex iteration
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PricingRule {
+    /// Chooses the column with the most negative reduced cost
+    Dantzig,
+
+    /// Chooses the column with the most negative reduced cost relative to the length
+    /// of its edge, computed exactly from the tableau, or updated exactly on each pivot
+    /// in the revised simplex method.  This usually takes the fewest iterations, but
+    /// each is more expensive.
+    SteepestEdge,
+
+    /// Approximates steepest edge with reference weights that are updated on each pivot
+    Devex,
+
+    /// Only prices a segment of the columns at a time, moving on to the next segment
+    /// when none of its columns can enter
+    Partial,
+}
+
+impl Default for PricingRule {
+    fn default() -> PricingRule {
+        PricingRule::Dantzig
+    }
+}
+
+/// Options controlling how a problem is solved, see `Problem::solve_with`
+#[derive(Debug, Clone, Default)]
+pub struct SolverOptions {
+    pub tolerances: Tolerances,
+
+    /// The maximum number of simplex iterations, in total across all phases and, for
+    /// brute force, across the subproblems of the search.  If not set, each phase is
+    /// limited based on the size of the problem.
+    pub iteration_limit: Option<u64>,
+
+    /// The rule for choosing the entering column in the simplex method
+    pub pricing: PricingRule,
+
+    /// The algorithm to use.  If not set, it is chosen based on the objective and the
+    /// kinds of variables in the problem.
+    pub algorithm: Option<Algorithm>,
+
+    /// The maximum wall clock time for the solve.  When it is reached, the solve stops
+    /// with a `Feasible` status and the best solution found so far, or with a
+    /// `TimeLimit` status if there is none.
+    pub time_limit: Option<Duration>,
+
+    /// A flag that may be set from another thread to stop the solve.  When it is set,
+    /// the solve stops with a `Feasible` status and the best solution found so far, or
+    /// with an `Interrupted` status if there is none.
+    pub cancel: Option<Arc<AtomicBool>>,
+
+    /// Called as the solve progresses.  See `Progress` for when it is called.
+    pub progress: Option<ProgressCallback>,
+
+    /// The basis to start from, usually the basis of an earlier solution before the
+    /// problem was modified.  Only used by the revised and dual simplex methods.
+    pub basis: Option<Basis>,
+
+    /// Returns the interior point solution as found, without the crossover to an
+    /// optimal basic solution.  Such a solution has no basis.  Only used by the
+    /// interior point method.
+    pub skip_crossover: bool,
+}
+
+/// The progress of a solve, as reported to a `ProgressCallback`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Progress {
+    /// Reported after each simplex iteration.  In phase 1, the objective is the total
+    /// infeasibility of the problem, while in phase 2 it is the problem objective.
+    Simplex { iteration: u64, objective: f64, phase: u32 },
+
+    /// Reported after each interior point iteration, with the objective and relative
+    /// duality gap of the point before the iteration
+    InteriorPoint { iteration: u64, objective: f64, gap: f64 },
+
+    /// Reported by the brute force search each time a new best solution is found
+    Incumbent { nodes: u64, objective: f64 },
+
+    /// Reported periodically by the brute force search, with the objective of the best
+    /// solution found so far
+    Nodes { nodes: u64, objective: Option<f64> },
+}
+
+/// Returned from a `ProgressCallback` to control the solve
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ProgressAction {
+    Continue,
+
+    /// Stops the solve with a `Feasible` status and the best solution found so far,
+    /// or with an `Interrupted` status if there is none
+    Terminate,
+}
+
+/// A function called with the progress of a solve
+#[derive(Clone)]
+pub struct ProgressCallback(Arc<dyn Fn(&Progress) -> ProgressAction + Send + Sync>);
+
+impl ProgressCallback {
+    pub fn new<F>(callback: F) -> ProgressCallback
+        where F: Fn(&Progress) -> ProgressAction + Send + Sync + 'static {
+        ProgressCallback(Arc::new(callback))
+    }
+}
+
+impl fmt::Debug for ProgressCallback {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ProgressCallback")
+    }
+}
+
+/// Checks whether a solve in progress should stop early, and reports its progress
+pub(crate) struct Interrupt {
+    deadline: Option<Instant>,
+    cancel: Option<Arc<AtomicBool>>,
+    progress: Option<ProgressCallback>,
+}
+
+impl Interrupt {
+    pub(crate) fn new(options: &SolverOptions, start: Instant) -> Interrupt {
+        Interrupt {
+            deadline: options.time_limit.map(|limit| start + limit),
+            cancel: options.cancel.clone(),
+            progress: options.progress.clone(),
+        }
+    }
+
+    /// Returns a copy of this that does not report progress, for solving subproblems
+    pub(crate) fn without_progress(&self) -> Interrupt {
+        Interrupt {
+            deadline: self.deadline,
+            cancel: self.cancel.clone(),
+            progress: None,
+        }
+    }
+
+    /// Reports progress to the callback, if any.  Returns the status to stop with if
+    /// the callback requested termination.
+    pub(crate) fn report(&self, progress: Progress) -> Option<SolveStatus> {
+        let callback = self.progress.as_ref()?;
+        match (callback.0)(&progress) {
+            ProgressAction::Continue => None,
+            ProgressAction::Terminate => Some(SolveStatus::Interrupted),
+        }
+    }
+
+    /// Returns the status to stop with, if the solve has been cancelled or has reached
+    /// its time limit
+    pub(crate) fn check(&self) -> Option<SolveStatus> {
+        if let Some(cancel) = &self.cancel {
+            if cancel.load(Ordering::Relaxed) {
+                return Some(SolveStatus::Interrupted);
+            }
+        }
+
+        match self.deadline {
+            Some(deadline) if Instant::now() >= deadline => Some(SolveStatus::TimeLimit),
+            _ => None,
+        }
+    }
+}
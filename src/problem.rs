@@ -14,11 +14,14 @@
 //  You should have received a copy of the GNU General Public License
 //  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
 
-use std::f64;
-use std::fmt;
+use std::{f64, fmt, ops};
 use std::collections::HashMap;
+use std::time::Instant;
 
-use crate::{Result, SolverError, simplex, gaussian_elimination, brute};
+use crate::{Result, SolverError, SolveResult, Sensitivity, Basis, Tolerances, VerifyReport,
+    SolverOptions, Algorithm, simplex, revised_simplex, dual_simplex, interior_point,
+    gaussian_elimination, brute, verify};
+use crate::options::Interrupt;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum VariableKind {
@@ -34,6 +37,25 @@ pub struct Variable {
 
 impl Variable {
     pub fn kind(&self) -> VariableKind { self.kind }
+
+    pub fn index(&self) -> usize { self.index as usize }
+}
+
+/// A handle to a constraint in a problem, as returned by `Problem::add_constraint`.
+/// Constraints can also be referred to directly by index.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ConstraintRef {
+    index: usize,
+}
+
+impl ConstraintRef {
+    pub fn index(&self) -> usize { self.index }
+}
+
+impl From<usize> for ConstraintRef {
+    fn from(index: usize) -> ConstraintRef {
+        ConstraintRef { index }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -47,17 +69,16 @@ pub enum ConstraintKind {
     GreaterThanOrEqualTo,
     EqualTo,
     LessThanOrEqualTo,
+
+    /// `lower <= expr <= constant`, with the lower constant held here
+    Range(f64),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
 pub struct Expression {
     coeffs: HashMap<u32, f64>,
 }
 
-impl Default for Expression {
-    fn default() -> Self { Expression { coeffs: HashMap::new() } }
-}
-
 impl Expression {
     pub fn new(coeffs: &[f64]) -> Expression {
         let mut expr = Expression::default();
@@ -79,13 +100,210 @@ impl Expression {
         }
     }
 
+    /// Adds `coeff` times `variable` to this expression.  If the variable is already
+    /// present in the expression, the coefficients are summed.
     pub fn add_term(&mut self, coeff: f64, variable: Variable) {
-        self.coeffs.insert(variable.index, coeff);
+        *self.coeffs.entry(variable.index).or_insert(0.0) += coeff;
+    }
+
+    pub fn add_expr(&mut self, other: &Expression) {
+        for (index, coeff) in other.coeffs.iter() {
+            *self.coeffs.entry(*index).or_insert(0.0) += *coeff;
+        }
+    }
+
+    pub fn scale(&mut self, mult: f64) {
+        for coeff in self.coeffs.values_mut() {
+            *coeff *= mult;
+        }
     }
 
     pub fn iter(&self) -> impl Iterator<Item=(&u32, &f64)> {
         self.coeffs.iter()
     }
+
+    /// Returns the value of this expression with the variable values in `solution`
+    pub fn evaluate(&self, solution: &Solution) -> f64 {
+        self.coeffs.iter().map(|(index, coeff)| coeff * solution.value(*index as usize)).sum()
+    }
+
+    /// Creates the constraint `self <= constant`
+    pub fn le(self, constant: f64) -> Constraint {
+        Constraint::new(self, ConstraintKind::LessThanOrEqualTo, constant)
+    }
+
+    /// Creates the constraint `self >= constant`
+    pub fn ge(self, constant: f64) -> Constraint {
+        Constraint::new(self, ConstraintKind::GreaterThanOrEqualTo, constant)
+    }
+
+    /// Creates the constraint `self == constant`
+    pub fn eq(self, constant: f64) -> Constraint {
+        Constraint::new(self, ConstraintKind::EqualTo, constant)
+    }
+
+    /// Creates the constraint `lower <= self <= upper`
+    pub fn range(self, lower: f64, upper: f64) -> Constraint {
+        Constraint::new(self, ConstraintKind::Range(lower), upper)
+    }
+}
+
+impl From<Variable> for Expression {
+    fn from(variable: Variable) -> Expression {
+        let mut expr = Expression::default();
+        expr.add_term(1.0, variable);
+        expr
+    }
+}
+
+impl ops::Neg for Variable {
+    type Output = Expression;
+
+    fn neg(self) -> Expression {
+        -1.0 * self
+    }
+}
+
+impl ops::Mul<f64> for Variable {
+    type Output = Expression;
+
+    fn mul(self, rhs: f64) -> Expression {
+        let mut expr = Expression::default();
+        expr.add_term(rhs, self);
+        expr
+    }
+}
+
+impl ops::Mul<Variable> for f64 {
+    type Output = Expression;
+
+    fn mul(self, rhs: Variable) -> Expression {
+        rhs * self
+    }
+}
+
+impl ops::Add<Variable> for Variable {
+    type Output = Expression;
+
+    fn add(self, rhs: Variable) -> Expression {
+        Expression::from(self) + rhs
+    }
+}
+
+impl ops::Add<Expression> for Variable {
+    type Output = Expression;
+
+    fn add(self, rhs: Expression) -> Expression {
+        rhs + self
+    }
+}
+
+impl ops::Sub<Variable> for Variable {
+    type Output = Expression;
+
+    fn sub(self, rhs: Variable) -> Expression {
+        Expression::from(self) - rhs
+    }
+}
+
+impl ops::Sub<Expression> for Variable {
+    type Output = Expression;
+
+    fn sub(self, rhs: Expression) -> Expression {
+        Expression::from(self) - rhs
+    }
+}
+
+impl ops::Neg for Expression {
+    type Output = Expression;
+
+    fn neg(mut self) -> Expression {
+        self.scale(-1.0);
+        self
+    }
+}
+
+impl ops::Mul<f64> for Expression {
+    type Output = Expression;
+
+    fn mul(mut self, rhs: f64) -> Expression {
+        self.scale(rhs);
+        self
+    }
+}
+
+impl ops::Mul<Expression> for f64 {
+    type Output = Expression;
+
+    fn mul(self, rhs: Expression) -> Expression {
+        rhs * self
+    }
+}
+
+impl ops::MulAssign<f64> for Expression {
+    fn mul_assign(&mut self, rhs: f64) {
+        self.scale(rhs);
+    }
+}
+
+impl ops::Add<Expression> for Expression {
+    type Output = Expression;
+
+    fn add(mut self, rhs: Expression) -> Expression {
+        self += rhs;
+        self
+    }
+}
+
+impl ops::Add<Variable> for Expression {
+    type Output = Expression;
+
+    fn add(mut self, rhs: Variable) -> Expression {
+        self += rhs;
+        self
+    }
+}
+
+impl ops::Sub<Expression> for Expression {
+    type Output = Expression;
+
+    fn sub(mut self, rhs: Expression) -> Expression {
+        self -= rhs;
+        self
+    }
+}
+
+impl ops::Sub<Variable> for Expression {
+    type Output = Expression;
+
+    fn sub(mut self, rhs: Variable) -> Expression {
+        self -= rhs;
+        self
+    }
+}
+
+impl ops::AddAssign<Expression> for Expression {
+    fn add_assign(&mut self, rhs: Expression) {
+        self.add_expr(&rhs);
+    }
+}
+
+impl ops::AddAssign<Variable> for Expression {
+    fn add_assign(&mut self, rhs: Variable) {
+        self.add_term(1.0, rhs);
+    }
+}
+
+impl ops::SubAssign<Expression> for Expression {
+    fn sub_assign(&mut self, rhs: Expression) {
+        self.add_expr(&-rhs);
+    }
+}
+
+impl ops::SubAssign<Variable> for Expression {
+    fn sub_assign(&mut self, rhs: Variable) {
+        self.add_term(-1.0, rhs);
+    }
 }
 
 #[derive(Debug)]
@@ -93,6 +311,7 @@ pub struct Constraint {
     expr: Expression,
     kind: ConstraintKind,
     constant: f64,
+    name: Option<String>,
 }
 
 impl Constraint {
@@ -101,6 +320,24 @@ impl Constraint {
             expr,
             kind,
             constant,
+            name: None,
+        }
+    }
+
+    /// Sets the name of this constraint, which must be unique within a problem and a
+    /// valid LP format identifier.  Names of the form `c<index>` and `obj` are reserved.
+    pub fn named<S: Into<String>>(mut self, name: S) -> Constraint {
+        self.name = Some(name.into());
+        self
+    }
+
+    pub fn name(&self) -> Option<&str> { self.name.as_deref() }
+
+    /// Describes this constraint, at `index` in its problem, for use in messages
+    pub(crate) fn label(&self, index: usize) -> String {
+        match self.name() {
+            None => format!("{}", index),
+            Some(name) => format!("'{}'", name),
         }
     }
 
@@ -108,6 +345,18 @@ impl Constraint {
 
     pub fn expr(&self) -> &Expression { &self.expr }
 
+    /// Returns the distance from `activity`, the value of the expression, to the nearest
+    /// limit of this constraint.  This is positive if the constraint is strictly
+    /// satisfied, zero if it is binding, and negative if it is violated.
+    pub fn slack(&self, activity: f64) -> f64 {
+        match self.kind {
+            ConstraintKind::LessThanOrEqualTo => self.constant - activity,
+            ConstraintKind::GreaterThanOrEqualTo => activity - self.constant,
+            ConstraintKind::EqualTo => -(activity - self.constant).abs(),
+            ConstraintKind::Range(lower) => (activity - lower).min(self.constant - activity),
+        }
+    }
+
     pub fn constant(&self) -> f64 {
         self.constant
     }
@@ -116,6 +365,13 @@ impl Constraint {
 pub struct Solution {
     coeffs: Vec<f64>,
     objective: Option<f64>,
+    names: Vec<Option<String>>,
+    duals: Option<Vec<f64>>,
+    reduced_costs: Option<Vec<f64>>,
+    basis: Option<Basis>,
+    activities: Vec<f64>,
+    slacks: Vec<f64>,
+    binding: Vec<bool>,
 }
 
 impl PartialEq for Solution {
@@ -128,7 +384,7 @@ impl PartialEq for Solution {
             if self.coeffs[i] != other.coeffs[i] { return false; }
         }
 
-        return true;
+        true
     }
 }
 
@@ -137,13 +393,61 @@ impl Solution {
         Solution {
             coeffs,
             objective,
+            names: Vec::new(),
+            duals: None,
+            reduced_costs: None,
+            basis: None,
+            activities: Vec::new(),
+            slacks: Vec::new(),
+            binding: Vec::new(),
         }
     }
 
+    pub(crate) fn set_activities(&mut self, activities: Vec<f64>, slacks: Vec<f64>, binding: Vec<bool>) {
+        self.activities = activities;
+        self.slacks = slacks;
+        self.binding = binding;
+    }
+
+    pub(crate) fn set_basis(&mut self, basis: Basis) {
+        self.basis = Some(basis);
+    }
+
+    /// Returns the optimal basis, if this is the optimal solution of a continuous
+    /// linear program
+    pub fn basis(&self) -> Option<&Basis> {
+        self.basis.as_ref()
+    }
+
+    pub(crate) fn set_duals(&mut self, duals: Vec<f64>, reduced_costs: Vec<f64>) {
+        self.duals = Some(duals);
+        self.reduced_costs = Some(reduced_costs);
+    }
+
+    pub(crate) fn set_names(&mut self, names: Vec<Option<String>>) {
+        self.names = names;
+    }
+
+    /// Returns the name of the variable at `index`, if it was named in the problem
+    pub fn name(&self, index: usize) -> Option<&str> {
+        self.names.get(index).and_then(|name| name.as_deref())
+    }
+
+    /// Returns the value of the variable with the specified name, or `None` if no
+    /// variable in the problem had that name
+    pub fn value_by_name(&self, name: &str) -> Option<f64> {
+        let index = self.names.iter().position(|n| n.as_deref() == Some(name))?;
+        Some(self.coeffs[index])
+    }
+
     pub fn value(&self, index: usize) -> f64 {
         self.coeffs[index]
     }
 
+    pub fn value_of(&self, variable: Variable) -> f64 {
+        self.coeffs[variable.index()]
+    }
+
     pub fn iter(&self) -> impl Iterator<Item=&f64> {
         self.coeffs.iter()
     }
@@ -151,6 +455,58 @@ impl Solution {
     pub fn objective(&self) -> Option<f64> {
         self.objective
     }
+
+    /// Returns the value of the expression of the specified constraint, which may be
+    /// given by index or by handle.  Returns `None` if this solution does not cover the
+    /// constraint, which is the case for any solution not produced by `Problem::solve`.
+    pub fn activity<C: Into<ConstraintRef>>(&self, constraint: C) -> Option<f64> {
+        self.activities.get(constraint.into().index).copied()
+    }
+
+    /// Returns the slack or surplus of the specified constraint.  See `Constraint::slack`.
+    pub fn slack<C: Into<ConstraintRef>>(&self, constraint: C) -> Option<f64> {
+        self.slacks.get(constraint.into().index).copied()
+    }
+
+    /// Returns whether the specified constraint holds with equality
+    pub fn is_binding<C: Into<ConstraintRef>>(&self, constraint: C) -> Option<bool> {
+        self.binding.get(constraint.into().index).copied()
+    }
+
+    pub fn activities(&self) -> impl Iterator<Item=&f64> {
+        self.activities.iter()
+    }
+
+    /// Returns the dual value (shadow price) of each constraint, if this is the optimal
+    /// solution of a continuous linear program.  The dual value is the rate at which
+    /// the objective changes as the constraint constant increases.
+    pub fn duals(&self) -> Option<&[f64]> {
+        self.duals.as_deref()
+    }
+
+    /// Returns the dual value of the constraint at `index`, or `None` if there are no
+    /// duals or `index` is out of range.  See `duals`.
+    pub fn dual(&self, index: usize) -> Option<f64> {
+        self.duals.as_ref().and_then(|duals| duals.get(index).copied())
+    }
+
+    /// Returns the reduced cost of each variable, if this is the optimal solution of a
+    /// continuous linear program.  The reduced cost is the rate at which the objective
+    /// changes as the variable moves away from its current value, with the basic
+    /// variables adjusting to keep the constraints satisfied.
+    pub fn reduced_costs(&self) -> Option<&[f64]> {
+        self.reduced_costs.as_deref()
+    }
+
+    /// Returns the reduced cost of the variable at `index`, or `None` if there are no
+    /// reduced costs or `index` is out of range.  See `reduced_costs`.
+    pub fn reduced_cost(&self, index: usize) -> Option<f64> {
+        self.reduced_costs.as_ref().and_then(|costs| costs.get(index).copied())
+    }
+
+    pub fn reduced_cost_of(&self, variable: Variable) -> Option<f64> {
+        self.reduced_cost(variable.index())
+    }
 }
 
 impl fmt::Debug for Solution {
@@ -160,7 +516,10 @@ impl fmt::Debug for Solution {
         }
 
         for (index, value) in self.coeffs.iter().enumerate() {
-            writeln!(f, "x[{}] = {:.6}", index, value)?;
+            match self.name(index) {
+                None => writeln!(f, "x[{}] = {:.6}", index, value)?,
+                Some(name) => writeln!(f, "{} = {:.6}", name, value)?,
+            }
         }
         Ok(())
     }
@@ -221,19 +580,25 @@ struct Objective {
 }
 
 #[derive(Debug)]
+struct VariableData {
+    variable: Variable,
+    name: Option<String>,
+    lower: f64,
+    upper: f64,
+}
+
+#[derive(Debug, Default)]
 pub struct Problem {
-    variables: Vec<Variable>,
+    variables: Vec<VariableData>,
+    variable_names: HashMap<String, u32>,
     constraints: Vec<Constraint>,
+    constraint_names: HashMap<String, usize>,
     objective: Option<Objective>,
 }
 
 impl Problem {
     pub fn new() -> Problem {
-        Problem {
-            variables: Vec::new(),
-            constraints: Vec::new(),
-            objective: None,
-        }
+        Problem::default()
     }
 
     pub fn boolean(vars: u32) -> Problem {
@@ -260,29 +625,63 @@ impl Problem {
         &self.constraints
     }
 
+    /// Returns the index of the constraint with the specified name
+    pub fn constraint_index(&self, name: &str) -> Option<usize> {
+        self.constraint_names.get(name).copied()
+    }
+
+    /// Returns a description of the constraint at `index` suitable for use in messages
+    pub(crate) fn constraint_label(&self, index: usize) -> String {
+        self.constraints[index].label(index)
+    }
+
     pub fn objective(&self) -> Option<(&Expression, ObjectiveKind)> {
-        match &self.objective {
-            None => None,
-            Some(obj) => Some((&obj.expr, obj.kind)),
-        }
+        self.objective.as_ref().map(|obj| (&obj.expr, obj.kind))
     }
 
     pub fn objective_kind(&self) -> Option<ObjectiveKind> {
-        match &self.objective {
-            None => None,
-            Some(obj) => Some(obj.kind),
-        }
+        self.objective.as_ref().map(|obj| obj.kind)
     }
 
     pub fn objective_expr(&self) -> Option<&Expression> {
-        match &self.objective {
-            None => None,
-            Some(obj) => Some(&obj.expr)
-        }
+        self.objective.as_ref().map(|obj| &obj.expr)
     }
 
     pub fn variables(&self) -> impl Iterator<Item=&Variable> {
-        self.variables.iter()
+        self.variables.iter().map(|data| &data.variable)
+    }
+
+    /// Looks up the variable with the specified name
+    pub fn variable(&self, name: &str) -> Option<Variable> {
+        self.variable_names.get(name).map(|index| self.variables[*index as usize].variable)
+    }
+
+    pub fn variable_name(&self, variable: Variable) -> Option<&str> {
+        self.variables.get(variable.index()).and_then(|data| data.name.as_deref())
+    }
+
+    /// Sets the name of the specified variable.  Names must be unique within a problem
+    /// and valid LP format identifiers.  Names of the form `x<index>` are reserved for
+    /// writing unnamed variables.
+    pub fn set_variable_name<S: Into<String>>(
+        &mut self,
+        variable: Variable,
+        name: S
+    ) -> Result<()> {
+        let name = name.into();
+        if variable.index() >= self.variables.len() {
+            return SolverError::invalid_variable(
+                format!("Variable {} does not exist in this problem.", variable.index()));
+        }
+
+        self.check_variable_name(&name)?;
+
+        if let Some(old) = self.variables[variable.index()].name.take() {
+            self.variable_names.remove(&old);
+        }
+        self.variable_names.insert(name.clone(), variable.index);
+        self.variables[variable.index()].name = Some(name);
+        Ok(())
     }
 
     pub fn num_variables(&self) -> usize {
@@ -298,73 +697,412 @@ impl Problem {
         values: &[f64],
         kind: ConstraintKind,
         constant: f64
-    ) -> Result<()> {
+    ) -> Result<ConstraintRef> {
         let expr = Expression::new(values);
         let constraint = Constraint::new(expr, kind, constant);
         self.add_constraint(constraint)
     }
 
-    pub fn add_variables(&mut self, kind: VariableKind, num: u32) {
-        for i in 0..num {
-            let variable = Variable {
-                index: i,
-                kind: kind,
-            };
-            self.variables.push(variable);
-        }
+    /// Adds `num` new variables of the specified kind to this problem, returning
+    /// handles to them.  Indices continue on from any previously added variables.
+    pub fn add_variables(&mut self, kind: VariableKind, num: u32) -> Vec<Variable> {
+        (0..num).map(|_| self.add_variable(kind)).collect()
     }
 
-    pub fn add_variable(&mut self, kind: VariableKind) {
+    pub fn add_variable(&mut self, kind: VariableKind) -> Variable {
         let index = self.variables.len();
         let variable = Variable {
             index: index as u32,
-            kind: kind,
+            kind,
+        };
+        let (lower, upper) = match kind {
+            VariableKind::Continuous => (0.0, f64::INFINITY),
+            VariableKind::Integer(min, max) => (min as f64, max as f64),
         };
-        self.variables.push(variable);
+        self.variables.push(VariableData { variable, name: None, lower, upper });
+        variable
+    }
+
+    /// Returns the `(lower, upper)` bounds of the specified variable.  Continuous
+    /// variables default to `(0, inf)`, while integer variables are bounded by the
+    /// range in their kind.
+    pub fn bounds(&self, variable: Variable) -> (f64, f64) {
+        let data = &self.variables[variable.index()];
+        (data.lower, data.upper)
+    }
+
+    /// Sets the bounds of the specified continuous variable.  Either bound may be
+    /// infinite, so `set_bounds(x, f64::NEG_INFINITY, f64::INFINITY)` makes `x` free,
+    /// and setting both bounds to the same value fixes `x` at that value.
+    pub fn set_bounds(&mut self, variable: Variable, lower: f64, upper: f64) -> Result<()> {
+        if variable.index() >= self.variables.len() {
+            return SolverError::invalid_variable(
+                format!("Variable {} does not exist in this problem.", variable.index()));
+        }
+
+        let label = self.variable_label(variable.index);
+        if let VariableKind::Integer(..) = self.variables[variable.index()].variable.kind {
+            return SolverError::invalid_variable(
+                format!("Bounds of integer variable {} are set by its kind.", label));
+        }
+
+        if lower.is_nan() || upper.is_nan() || lower > upper ||
+            lower == f64::INFINITY || upper == f64::NEG_INFINITY {
+            return SolverError::invalid_variable(
+                format!("Invalid bounds [{}, {}] for variable {}.", lower, upper, label));
+        }
+
+        let data = &mut self.variables[variable.index()];
+        data.lower = lower;
+        data.upper = upper;
+        Ok(())
     }
 
-    pub fn add_constraints(&mut self, constraints: Vec<Constraint>) -> Result<()> {
-        for constraint in constraints {
-            self.add_constraint(constraint)?;
+    pub fn add_named_variable<S: Into<String>>(
+        &mut self,
+        name: S,
+        kind: VariableKind
+    ) -> Result<Variable> {
+        let name = name.into();
+        self.check_variable_name(&name)?;
+
+        let variable = self.add_variable(kind);
+        self.set_variable_name(variable, name)?;
+        Ok(variable)
+    }
+
+    fn check_variable_name(&self, name: &str) -> Result<()> {
+        if let Some(reason) = invalid_name(name, 'x') {
+            return SolverError::invalid_variable(
+                format!("Variable name '{}' is invalid: {}.", name, reason));
+        }
+
+        if self.variable_names.contains_key(name) {
+            return SolverError::invalid_variable(
+                format!("A variable named '{}' already exists.", name));
         }
 
         Ok(())
     }
 
-    pub fn add_constraint(&mut self, constraint: Constraint) -> Result<()> {
-        if constraint.expr.coeffs.len() != self.variables.len() {
+    pub(crate) fn clear_constraints(&mut self) {
+        self.constraints.clear();
+        self.constraint_names.clear();
+    }
+
+    pub fn add_constraints(&mut self, constraints: Vec<Constraint>) -> Result<Vec<ConstraintRef>> {
+        constraints.into_iter().map(|constraint| self.add_constraint(constraint)).collect()
+    }
+
+    /// Adds the specified constraint to this problem, returning a handle to it.  The
+    /// constraint's expression only needs to contain the non-zero terms, but every term
+    /// must refer to a variable that has already been added to this problem.
+    pub fn add_constraint(&mut self, constraint: Constraint) -> Result<ConstraintRef> {
+        let label = constraint.label(self.constraints.len());
+
+        if let Some(index) = self.invalid_index(&constraint.expr) {
             return SolverError::invalid_constraint(
-                format!("Expected {} vars in constraint, got {}.",
-                    self.variables.len(), constraint.expr.coeffs.len()));
+                format!("Constraint {} references variable {}, but only {} vars exist.",
+                    label, index, self.variables.len()));
         }
+
+        if !constraint.constant.is_finite() {
+            return SolverError::invalid_constraint(
+                format!("Constraint {} has a non-finite constant.", label));
+        }
+
+        if let ConstraintKind::Range(lower) = constraint.kind {
+            if lower.is_nan() || lower == f64::INFINITY || lower > constraint.constant {
+                return SolverError::invalid_constraint(
+                    format!("Constraint {} has an empty range [{}, {}].",
+                        label, lower, constraint.constant));
+            }
+        }
+
+        if let Some(name) = constraint.name() {
+            let reason = match name {
+                "obj" => Some("it is reserved for the objective"),
+                _ => invalid_name(name, 'c'),
+            };
+            if let Some(reason) = reason {
+                return SolverError::invalid_constraint(
+                    format!("Constraint name {} is invalid: {}.", label, reason));
+            }
+
+            if self.constraint_names.contains_key(name) {
+                return SolverError::invalid_constraint(
+                    format!("A constraint named {} already exists.", label));
+            }
+            self.constraint_names.insert(name.to_string(), self.constraints.len());
+        }
+
         self.constraints.push(constraint);
+        Ok(ConstraintRef { index: self.constraints.len() - 1 })
+    }
+
+    /// Sets the constant of the specified constraint.  For a range constraint, this is
+    /// the upper constant, and it may not be moved below the lower constant.
+    pub fn set_constant<C: Into<ConstraintRef>>(&mut self, constraint: C, constant: f64) -> Result<()> {
+        let index = constraint.into().index();
+        if index >= self.constraints.len() {
+            return SolverError::invalid_constraint(
+                format!("Constraint {} does not exist in this problem.", index));
+        }
+
+        let label = self.constraint_label(index);
+        let constraint = &mut self.constraints[index];
+
+        if !constant.is_finite() {
+            return SolverError::invalid_constraint(
+                format!("Constraint {} has a non-finite constant.", label));
+        }
+
+        if let ConstraintKind::Range(lower) = constraint.kind {
+            if lower > constant {
+                return SolverError::invalid_constraint(
+                    format!("Constraint {} has an empty range [{}, {}].", label, lower, constant));
+            }
+        }
+
+        constraint.constant = constant;
         Ok(())
     }
 
+    fn invalid_index(&self, expr: &Expression) -> Option<u32> {
+        expr.coeffs.keys().find(|index| **index as usize >= self.variables.len()).copied()
+    }
+
+    /// Solves this problem, returning the solution only if it is optimal.  See
+    /// `solve_detailed` for the status and statistics of the solve.
     pub fn solve(&self) -> Result<Solution> {
+        self.solve_detailed()?.into_solution()
+    }
+
+    /// Solves this problem with the specified options, returning the solution only if
+    /// it is optimal
+    pub fn solve_with(&self, options: &SolverOptions) -> Result<Solution> {
+        self.solve_detailed_with(options)?.into_solution()
+    }
+
+    /// Solves this problem, selecting an algorithm based on the variables and objective.
+    /// Returns an error if the problem itself is invalid.
+    pub fn solve_detailed(&self) -> Result<SolveResult> {
+        self.solve_detailed_with(&SolverOptions::default())
+    }
+
+    /// Solves this problem with the specified options.  Unless the options force a
+    /// particular algorithm, one is selected based on the variables and objective.
+    pub fn solve_detailed_with(&self, options: &SolverOptions) -> Result<SolveResult> {
+        let start = Instant::now();
+
+        if let Some(expr) = self.objective_expr() {
+            if let Some(index) = self.invalid_index(expr) {
+                return SolverError::invalid_objective(
+                    format!("Objective references variable {}, but only {} vars exist.",
+                        index, self.variables.len()));
+            }
+        }
+
         use VariableKind::*;
         let mut var_kind = Continuous;
-        for var in &self.variables {
+        for var in self.variables() {
             if let Integer(..) = var.kind {
                 var_kind = Integer(0, 0);
             }
         }
 
+        let algorithm = match (options.algorithm, &self.objective, var_kind) {
+            (Some(algorithm), _, _) => algorithm,
+            (None, None, Continuous) => Algorithm::GaussianElimination,
+            (None, None, Integer(..)) => return SolverError::unable_to_solve(
+                "A mixed integer or integer problem must specify an objective."),
+            (None, Some(_), Continuous) => Algorithm::Simplex,
+            (None, Some(_), Integer(..)) => Algorithm::BruteForce,
+        };
 
-        match self.objective {
-            None => {
-                match var_kind {
-                    Continuous => gaussian_elimination::solve(self),
-                    Integer(..) => SolverError::unable_to_solve(
-                        "A mixed integer or integer problem must specify an objective."),
-                }
-            },
-            Some(_) => {
-                match var_kind {
-                    Continuous => simplex::solve(self),
-                    Integer(..) => brute::solve(self),
+        let interrupt = Interrupt::new(options, start);
+        let mut result = match algorithm {
+            Algorithm::GaussianElimination =>
+                gaussian_elimination::solve(self, options, &interrupt),
+            Algorithm::Simplex => simplex::solve(self, options, &interrupt),
+            Algorithm::RevisedSimplex => revised_simplex::solve(self, options, &interrupt),
+            Algorithm::DualSimplex => dual_simplex::solve(self, options, &interrupt),
+            Algorithm::InteriorPoint => interior_point::solve(self, options, &interrupt),
+            Algorithm::BruteForce => brute::solve(self, options, &interrupt),
+        }?;
+
+        if let Some(solution) = result.solution_mut() {
+            if !self.variable_names.is_empty() {
+                let names = self.variables.iter().map(|data| data.name.clone()).collect();
+                solution.set_names(names);
+            }
+            self.set_activities(solution, options.tolerances.feasibility);
+        }
+
+        result.stats_mut().elapsed = start.elapsed();
+        Ok(result)
+    }
+
+    /// Computes how far each objective coefficient and constraint constant can move
+    /// before the optimal basis of `solution` changes.  The solution must be an optimal
+    /// solution of this problem with a basis, see `Solution::basis`, and `tolerances`
+    /// should be those it was solved with so that the basis is classified the same way.
+    pub fn sensitivity(&self, solution: &Solution, tolerances: &Tolerances) -> Result<Sensitivity> {
+        match solution.basis() {
+            None => SolverError::invalid_solution(
+                "Sensitivity analysis requires the optimal basis of a linear program."),
+            Some(basis) => simplex::sensitivity(self, basis, tolerances),
+        }
+    }
+
+    /// Checks `solution` against every constraint, variable bound and integrality
+    /// requirement of this problem, and recomputes its objective value
+    pub fn verify(&self, solution: &Solution, tolerances: &Tolerances) -> Result<VerifyReport> {
+        verify::verify(self, solution, tolerances)
+    }
+
+    /// Attaches the constraint activities to `solution`, treating constraints with a
+    /// slack within `tol` of zero as binding
+    fn set_activities(&self, solution: &mut Solution, tol: f64) {
+        let activities: Vec<f64> = self.constraints.iter()
+            .map(|constraint| constraint.expr.evaluate(solution)).collect();
+
+        let slacks: Vec<f64> = self.constraints.iter().zip(activities.iter())
+            .map(|(constraint, activity)| constraint.slack(*activity)).collect();
+
+        let binding = slacks.iter().map(|slack| slack.abs() <= tol).collect();
+        solution.set_activities(activities, slacks, binding);
+    }
+
+    fn variable_label(&self, index: u32) -> String {
+        match &self.variables[index as usize].name {
+            None => format!("x{}", index),
+            Some(name) => name.clone(),
+        }
+    }
+
+    fn fmt_expr(&self, f: &mut fmt::Formatter, expr: &Expression) -> fmt::Result {
+        let mut terms: Vec<(u32, f64)> = expr.iter()
+            .filter(|(_, coeff)| **coeff != 0.0)
+            .map(|(index, coeff)| (*index, *coeff))
+            .collect();
+        terms.sort_by_key(|(index, _)| *index);
+
+        if terms.is_empty() {
+            return write!(f, " 0");
+        }
+
+        for (i, (index, coeff)) in terms.into_iter().enumerate() {
+            let sign = match (i, coeff < 0.0) {
+                (0, false) => "",
+                (0, true) => "-",
+                (_, false) => "+ ",
+                (_, true) => "- ",
+            };
+
+            let label = self.variable_label(index);
+            if coeff.abs() == 1.0 {
+                write!(f, " {}{}", sign, label)?;
+            } else {
+                write!(f, " {}{} {}", sign, coeff.abs(), label)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns why `name` can't be used in the LP file format, if it can't.  Names of the
+/// form `<prefix><index>` are reserved for unnamed items.
+fn invalid_name(name: &str, prefix: char) -> Option<&'static str> {
+    const SYMBOLS: &str = "!\"#$%&()/,.;?@_`'{}|~";
+
+    let first = match name.chars().next() {
+        None => return Some("it is empty"),
+        Some(first) => first,
+    };
+
+    if name.len() > 255 {
+        Some("it is longer than 255 characters")
+    } else if first.is_ascii_digit() || first == '.' {
+        Some("it starts with a digit or a period")
+    } else if !name.chars().all(|c| c.is_ascii_alphanumeric() || SYMBOLS.contains(c)) {
+        Some("it contains a character not allowed in the LP format")
+    } else if name.starts_with(prefix) && name.len() > 1 &&
+        name[1..].chars().all(|c| c.is_ascii_digit()) {
+        Some("it has the form used for unnamed items")
+    } else {
+        None
+    }
+}
+
+/// Writes the problem in the CPLEX LP file format.  Unnamed variables are written as
+/// `x<index>` and unnamed constraints as `c<index>`, which named items can't use.
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.objective {
+            Some(Objective { kind: ObjectiveKind::Maximize, .. }) => writeln!(f, "Maximize")?,
+            _ => writeln!(f, "Minimize")?,
+        }
+        write!(f, " obj:")?;
+        match &self.objective {
+            None => write!(f, " 0")?,
+            Some(objective) => self.fmt_expr(f, &objective.expr)?,
+        }
+        writeln!(f)?;
+
+        writeln!(f, "Subject To")?;
+        for (index, constraint) in self.constraints.iter().enumerate() {
+            match constraint.name() {
+                None => write!(f, " c{}:", index)?,
+                Some(name) => write!(f, " {}:", name)?,
+            }
+            if let ConstraintKind::Range(lower) = constraint.kind {
+                write!(f, " {} <=", lower)?;
+            }
+            self.fmt_expr(f, &constraint.expr)?;
+            let op = match constraint.kind {
+                ConstraintKind::GreaterThanOrEqualTo => ">=",
+                ConstraintKind::EqualTo => "=",
+                ConstraintKind::LessThanOrEqualTo | ConstraintKind::Range(_) => "<=",
+            };
+            writeln!(f, " {} {}", op, constraint.constant)?;
+        }
+
+        let bounded: Vec<&VariableData> = self.variables.iter()
+            .filter(|data| data.lower != 0.0 || data.upper != f64::INFINITY)
+            .collect();
+
+        if !bounded.is_empty() {
+            writeln!(f, "Bounds")?;
+            for data in bounded {
+                let label = self.variable_label(data.variable.index);
+                let (lower, upper) = (data.lower, data.upper);
+                if lower == upper {
+                    writeln!(f, " {} = {}", label, lower)?;
+                } else if lower == f64::NEG_INFINITY && upper == f64::INFINITY {
+                    writeln!(f, " {} free", label)?;
+                } else if upper == f64::INFINITY {
+                    writeln!(f, " {} >= {}", label, lower)?;
+                } else if lower == f64::NEG_INFINITY {
+                    writeln!(f, " -inf <= {} <= {}", label, upper)?;
+                } else {
+                    writeln!(f, " {} <= {} <= {}", lower, label, upper)?;
                 }
-            },
+            }
         }
+
+        let integers: Vec<&VariableData> = self.variables.iter()
+            .filter(|data| data.variable.kind != VariableKind::Continuous)
+            .collect();
+
+        if !integers.is_empty() {
+            writeln!(f, "General")?;
+            for data in integers.iter() {
+                writeln!(f, " {}", self.variable_label(data.variable.index))?;
+            }
+        }
+
+        writeln!(f, "End")
     }
 }
@@ -0,0 +1,916 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::f64;
+
+use log::{debug, info, warn};
+
+use crate::{ConstraintKind, Problem, Solution, ObjectiveKind, Result, SolverError, SolveResult,
+    SolveStatus, SolveStats, SolverOptions, Tolerances, Progress, PricingRule, Basis};
+use crate::options::Interrupt;
+use crate::simplex::{BasisStatus, DEGENERATE_PIVOT_LIMIT, PARTIAL_SEGMENT_MIN};
+
+/// The number of eta columns kept after a factorization before the basis is factorized
+/// again
+const REFACTOR_INTERVAL: usize = 64;
+
+/// When factorizing, entries smaller than this fraction of the largest entry in their
+/// column are not used as pivots
+const PIVOT_THRESHOLD: f64 = 0.1;
+
+/// One step of the elimination producing the LU factors of the basis
+struct Elimination {
+    row: usize,
+    position: usize,
+    pivot: f64,
+
+    /// The multiple of the pivot row subtracted from each other row
+    lower: Vec<(usize, f64)>,
+
+    /// The entries of the pivot row in the basis positions not yet eliminated
+    upper: Vec<(usize, f64)>,
+}
+
+/// The change to the basis from a single pivot, in product form.  The new basis is
+/// the old one with the column at `position` replaced, which is `column` when
+/// expressed in terms of the old basis.
+struct Eta {
+    position: usize,
+    pivot: f64,
+
+    /// The entries of the column other than the pivot
+    column: Vec<(usize, f64)>,
+}
+
+/// A sparse LU factorization of the basis, followed by the eta columns of each pivot
+/// since the factorization
+pub(crate) struct Factor {
+    steps: Vec<Elimination>,
+    etas: Vec<Eta>,
+}
+
+impl Factor {
+    /// Factorizes the square matrix with the given sparse columns, choosing pivots to
+    /// limit fill in.  Returns `None` if the matrix is singular.
+    fn new(columns: &[&[(usize, f64)]], tol: f64) -> Option<Factor> {
+        let size = columns.len();
+        let mut rows: Vec<BTreeMap<usize, f64>> = vec![BTreeMap::new(); size];
+        let mut col_rows: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); size];
+        for (position, column) in columns.iter().enumerate() {
+            for (row, value) in column.iter() {
+                if *value == 0.0 { continue; }
+                rows[*row].insert(position, *value);
+                col_rows[position].insert(*row);
+            }
+        }
+
+        let mut done = vec![false; size];
+        let mut steps = Vec::with_capacity(size);
+        for _ in 0..size {
+            // the sparsest remaining column, and then the sparsest row among the
+            // numerically acceptable entries of that column
+            let position = (0..size).filter(|position| !done[*position])
+                .min_by_key(|position| col_rows[*position].len())?;
+
+            let max = col_rows[position].iter()
+                .map(|row| rows[*row][&position].abs())
+                .fold(0.0, f64::max);
+            if max <= tol { return None; }
+
+            let row = *col_rows[position].iter()
+                .filter(|row| rows[**row][&position].abs() >= PIVOT_THRESHOLD * max)
+                .min_by_key(|row| rows[**row].len())?;
+
+            let pivot_row = std::mem::take(&mut rows[row]);
+            let pivot = pivot_row[&position];
+            let upper: Vec<(usize, f64)> = pivot_row.into_iter()
+                .filter(|(other, _)| *other != position).collect();
+
+            let mut lower = Vec::new();
+            for other in std::mem::take(&mut col_rows[position]) {
+                if other == row { continue; }
+
+                let mult = rows[other].remove(&position).unwrap() / pivot;
+                for (col, value) in upper.iter() {
+                    let entry = rows[other].entry(*col).or_insert(0.0);
+                    *entry -= mult * value;
+                    col_rows[*col].insert(other);
+                }
+                lower.push((other, mult));
+            }
+
+            for (col, _) in upper.iter() {
+                col_rows[*col].remove(&row);
+            }
+
+            done[position] = true;
+            steps.push(Elimination { row, position, pivot, lower, upper });
+        }
+
+        Some(Factor { steps, etas: Vec::new() })
+    }
+
+    /// Solves `B x = rhs`, with `rhs` indexed by row and `x` by basis position
+    pub(crate) fn ftran(&self, mut rhs: Vec<f64>) -> Vec<f64> {
+        for step in self.steps.iter() {
+            let value = rhs[step.row];
+            if value == 0.0 { continue; }
+            for (row, mult) in step.lower.iter() {
+                rhs[*row] -= mult * value;
+            }
+        }
+
+        let mut x = vec![0.0; rhs.len()];
+        for step in self.steps.iter().rev() {
+            let mut value = rhs[step.row];
+            for (position, entry) in step.upper.iter() {
+                value -= entry * x[*position];
+            }
+            x[step.position] = value / step.pivot;
+        }
+
+        for eta in self.etas.iter() {
+            let value = x[eta.position] / eta.pivot;
+            x[eta.position] = value;
+            if value == 0.0 { continue; }
+            for (position, entry) in eta.column.iter() {
+                x[*position] -= entry * value;
+            }
+        }
+
+        x
+    }
+
+    /// Solves `y B = rhs`, with `rhs` indexed by basis position and `y` by row
+    pub(crate) fn btran(&self, mut rhs: Vec<f64>) -> Vec<f64> {
+        for eta in self.etas.iter().rev() {
+            let mut value = rhs[eta.position];
+            for (position, entry) in eta.column.iter() {
+                value -= entry * rhs[*position];
+            }
+            rhs[eta.position] = value / eta.pivot;
+        }
+
+        let mut y = vec![0.0; rhs.len()];
+        for step in self.steps.iter() {
+            let value = rhs[step.position] / step.pivot;
+            y[step.row] = value;
+            if value == 0.0 { continue; }
+            for (position, entry) in step.upper.iter() {
+                rhs[*position] -= entry * value;
+            }
+        }
+
+        for step in self.steps.iter().rev() {
+            let value: f64 = step.lower.iter().map(|(row, mult)| mult * y[*row]).sum();
+            y[step.row] -= value;
+        }
+
+        y
+    }
+
+    /// Records the pivot replacing the basis column at `position` with the column that
+    /// is `column` in terms of the current basis
+    fn update(&mut self, position: usize, column: &[f64]) {
+        let entries = column.iter().enumerate()
+            .filter(|(index, value)| *index != position && **value != 0.0)
+            .map(|(index, value)| (index, *value))
+            .collect();
+        self.etas.push(Eta { position, pivot: column[position], column: entries });
+    }
+}
+
+/// The state of the pricing rule over the course of a solve
+struct Pricing {
+    rule: PricingRule,
+
+    /// The Devex reference weight or steepest edge weight of each column
+    weights: Vec<f64>,
+
+    /// The column to start the next segment at, for partial pricing
+    next: usize,
+}
+
+#[derive(Debug)]
+enum Step {
+    /// The entering column reaches its opposite bound before any basic variable
+    Flip,
+
+    /// The basic variable at the position leaves the basis.  If `to_upper` is set, it
+    /// leaves at its upper bound
+    Pivot { position: usize, to_upper: bool },
+}
+
+/// The revised simplex method.  Rather than the full tableau, this keeps the sparse
+/// constraint columns and a factorization of the basis, from which the prices and the
+/// entering column are computed in each iteration.  The columns hold one column per
+/// problem variable followed by one logical column per constraint, each constraint
+/// being written as `a.x + s = b`.  Costs are always minimized; maximization problems
+/// have their costs negated.
+pub(crate) struct Revised {
+    pub(crate) columns: Vec<Vec<(usize, f64)>>,
+    pub(crate) lower: Vec<f64>,
+    pub(crate) upper: Vec<f64>,
+    costs: Vec<f64>,
+    constants: Vec<f64>,
+
+    /// The value of every column, basic or not
+    pub(crate) values: Vec<f64>,
+    pub(crate) statuses: Vec<BasisStatus>,
+
+    /// The column at each basis position
+    pub(crate) basis: Vec<usize>,
+    pub(crate) factor: Factor,
+
+    num_variables: usize,
+    objective_kind: ObjectiveKind,
+    pub(crate) tolerances: Tolerances,
+}
+
+impl Revised {
+    /// Sets up the problem, starting from `basis` if there is one, and otherwise from
+    /// the basis of logical columns
+    pub(crate) fn new(
+        problem: &Problem,
+        objective_kind: ObjectiveKind,
+        tolerances: Tolerances,
+        basis: Option<&Basis>,
+    ) -> Result<Revised> {
+        let num_variables = problem.num_variables();
+        let num_constraints = problem.num_constraints();
+        info!("Set up revised simplex problem with {} constraints and {} variables",
+              num_constraints, num_variables);
+
+        let mut columns = vec![Vec::new(); num_variables + num_constraints];
+        let mut lower = Vec::with_capacity(columns.len());
+        let mut upper = Vec::with_capacity(columns.len());
+        for variable in problem.variables() {
+            let (min, max) = problem.bounds(*variable);
+            lower.push(min);
+            upper.push(max);
+        }
+
+        let mut constants = Vec::with_capacity(num_constraints);
+        for (i, constraint) in problem.constraints().iter().enumerate() {
+            let mut terms: Vec<(usize, f64)> = constraint.expr().iter()
+                .filter(|(_, value)| **value != 0.0)
+                .map(|(index, value)| (*index as usize, *value)).collect();
+            terms.sort_by_key(|(index, _)| *index);
+            for (index, value) in terms {
+                columns[index].push((i, value));
+            }
+
+            let (min, max) = match constraint.kind() {
+                ConstraintKind::LessThanOrEqualTo => (0.0, f64::INFINITY),
+                ConstraintKind::GreaterThanOrEqualTo => (f64::NEG_INFINITY, 0.0),
+                ConstraintKind::EqualTo => (0.0, 0.0),
+                ConstraintKind::Range(lower) => (0.0, constraint.constant() - lower),
+            };
+            columns[num_variables + i].push((i, 1.0));
+            lower.push(min);
+            upper.push(max);
+            constants.push(constraint.constant());
+        }
+
+        let mult = match objective_kind {
+            ObjectiveKind::Minimize => 1.0,
+            ObjectiveKind::Maximize => -1.0,
+        };
+        let mut costs = vec![0.0; columns.len()];
+        if let Some(expr) = problem.objective_expr() {
+            for (index, value) in expr.iter() {
+                costs[*index as usize] = mult * value;
+            }
+        }
+
+        // non-basic columns start at a finite bound where there is one, and the logical
+        // columns form the initial basis
+        let mut values = vec![0.0; columns.len()];
+        let mut statuses = vec![BasisStatus::Basic; columns.len()];
+        for index in 0..num_variables {
+            if lower[index].is_finite() {
+                values[index] = lower[index];
+                statuses[index] = BasisStatus::AtLower;
+            } else if upper[index].is_finite() {
+                values[index] = upper[index];
+                statuses[index] = BasisStatus::AtUpper;
+            } else {
+                statuses[index] = BasisStatus::Free;
+            }
+        }
+
+        let basic: Vec<usize> = (num_variables..columns.len()).collect();
+        let mut revised = Revised {
+            columns,
+            lower,
+            upper,
+            costs,
+            constants,
+            values,
+            statuses,
+            basis: basic,
+            factor: Factor { steps: Vec::new(), etas: Vec::new() },
+            num_variables,
+            objective_kind,
+            tolerances,
+        };
+        revised.refactor()?;
+        if let Some(basis) = basis {
+            revised.warm_start(basis)?;
+        }
+        Ok(revised)
+    }
+
+    /// Starts from the given basis, such as the optimal basis of the problem before it
+    /// was modified.  Variables and constraints added since keep their initial status,
+    /// so the logical columns of new constraints are basic.  If the basis is not valid
+    /// for the modified problem, the initial basis is kept instead.
+    fn warm_start(&mut self, basis: &Basis) -> Result<()> {
+        let (old_variables, old_constraints) = (basis.num_variables(), basis.num_constraints());
+        if old_variables > self.num_variables || old_constraints > self.num_constraints() {
+            return SolverError::invalid_solution(format!("Basis has {} variables and {} \
+                constraints, but the problem has only {} and {}.", old_variables,
+                old_constraints, self.num_variables, self.num_constraints()));
+        }
+
+        let mut statuses = self.statuses.clone();
+        let mut values = self.values.clone();
+        for (index, status) in basis.statuses().iter().enumerate() {
+            let col = if index < old_variables {
+                index
+            } else {
+                index - old_variables + self.num_variables
+            };
+
+            // statuses no longer matching the bounds of their column are left as they were
+            let (lower, upper) = (self.lower[col], self.upper[col]);
+            values[col] = match status {
+                BasisStatus::Basic => 0.0,
+                BasisStatus::AtLower if lower.is_finite() => lower,
+                BasisStatus::AtUpper if upper.is_finite() => upper,
+                BasisStatus::Free if !lower.is_finite() && !upper.is_finite() => 0.0,
+                _ => continue,
+            };
+            statuses[col] = *status;
+        }
+
+        let basic: Vec<usize> = (0..statuses.len())
+            .filter(|col| statuses[*col] == BasisStatus::Basic).collect();
+        let factor = if basic.len() == self.num_constraints() {
+            let columns: Vec<&[(usize, f64)]> = basic.iter()
+                .map(|col| self.columns[*col].as_slice()).collect();
+            Factor::new(&columns, self.tolerances.pivot)
+        } else {
+            None
+        };
+
+        match factor {
+            None => warn!("Starting basis is not valid for the problem, ignoring it"),
+            Some(factor) => {
+                info!("Starting from the specified basis");
+                self.statuses = statuses;
+                self.values = values;
+                self.basis = basic;
+                self.factor = factor;
+                self.compute_basic_values();
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn num_constraints(&self) -> usize {
+        self.basis.len()
+    }
+
+    /// Factorizes the current basis, discarding any eta columns, and recomputes the
+    /// values of the basic columns from the non-basic ones
+    pub(crate) fn refactor(&mut self) -> Result<()> {
+        debug!("Factorizing basis {:?}", self.basis);
+        let columns: Vec<&[(usize, f64)]> = self.basis.iter()
+            .map(|col| self.columns[*col].as_slice()).collect();
+        self.factor = match Factor::new(&columns, self.tolerances.pivot) {
+            None => return SolverError::unable_to_solve("Simplex basis became singular."),
+            Some(factor) => factor,
+        };
+
+        self.compute_basic_values();
+        Ok(())
+    }
+
+    fn compute_basic_values(&mut self) {
+        let mut rhs = self.constants.clone();
+        for (col, column) in self.columns.iter().enumerate() {
+            let value = self.values[col];
+            if self.statuses[col] == BasisStatus::Basic || value == 0.0 { continue; }
+            for (row, entry) in column.iter() {
+                rhs[*row] -= entry * value;
+            }
+        }
+
+        let basic_values = self.factor.ftran(rhs);
+        for (position, col) in self.basis.iter().enumerate() {
+            self.values[*col] = basic_values[position];
+        }
+    }
+
+    /// Moves the entering column by `step`, updating the values of the basic columns,
+    /// where `alpha` is the entering column in terms of the basis
+    pub(crate) fn advance(&mut self, entering: usize, alpha: &[f64], step: f64) {
+        for (position, col) in self.basis.iter().enumerate() {
+            self.values[*col] -= step * alpha[position];
+        }
+        self.values[entering] += step;
+    }
+
+    /// Replaces the basic column at `position` with `entering`, where `alpha` is the
+    /// entering column in terms of the basis.  The leaving column is placed at its upper
+    /// bound if `to_upper` is set, and otherwise at its lower bound.
+    pub(crate) fn replace(
+        &mut self,
+        position: usize,
+        entering: usize,
+        alpha: &[f64],
+        to_upper: bool,
+    ) -> Result<()> {
+        let leaving = self.basis[position];
+        info!("Column {} leaves the basis", leaving);
+
+        // fixed columns are always reported at their lower bound
+        let to_upper = to_upper && self.lower[leaving] != self.upper[leaving];
+        let (value, status) = if to_upper {
+            (self.upper[leaving], BasisStatus::AtUpper)
+        } else {
+            (self.lower[leaving], BasisStatus::AtLower)
+        };
+        self.values[leaving] = value;
+        self.statuses[leaving] = status;
+        self.statuses[entering] = BasisStatus::Basic;
+        self.basis[position] = entering;
+
+        self.factor.update(position, alpha);
+        if self.factor.etas.len() >= REFACTOR_INTERVAL {
+            self.refactor()?;
+        }
+        Ok(())
+    }
+
+    /// The prices of the constraint rows for the phase II costs
+    pub(crate) fn prices(&self) -> Vec<f64> {
+        self.factor.btran(self.basic_costs(2))
+    }
+
+    /// The phase II reduced cost of the column for the prices `y`
+    pub(crate) fn reduced_cost(&self, y: &[f64], col: usize) -> f64 {
+        self.costs[col] - self.dot(y, col)
+    }
+
+    /// The column scattered into a dense vector indexed by row
+    pub(crate) fn dense_column(&self, col: usize) -> Vec<f64> {
+        let mut dense = vec![0.0; self.num_constraints()];
+        for (row, value) in self.columns[col].iter() {
+            dense[*row] = *value;
+        }
+        dense
+    }
+
+    pub(crate) fn dot(&self, y: &[f64], col: usize) -> f64 {
+        self.columns[col].iter().map(|(row, value)| y[*row] * value).sum()
+    }
+
+    /// The amount by which the basic column is outside its bounds, as a negative
+    /// number below the lower bound and a positive number above the upper bound
+    pub(crate) fn infeasibility(&self, col: usize) -> f64 {
+        let value = self.values[col];
+        let tol = self.tolerances.feasibility;
+        if value < self.lower[col] - tol {
+            value - self.lower[col]
+        } else if value > self.upper[col] + tol {
+            value - self.upper[col]
+        } else {
+            0.0
+        }
+    }
+
+    fn total_infeasibility(&self) -> f64 {
+        self.basis.iter().map(|col| self.infeasibility(*col).abs()).sum()
+    }
+
+    /// The costs of the basic columns.  In phase I, each column below its lower bound
+    /// costs -1 and each column above its upper bound costs 1, so that the total
+    /// infeasibility is minimized.
+    fn basic_costs(&self, phase: u32) -> Vec<f64> {
+        self.basis.iter().map(|col| {
+            let infeasibility = self.infeasibility(*col);
+            if phase == 2 {
+                self.costs[*col]
+            } else if infeasibility < 0.0 {
+                -1.0
+            } else if infeasibility > 0.0 {
+                1.0
+            } else {
+                0.0
+            }
+        }).collect()
+    }
+
+    /// The value of the objective being minimized.  In phase II, this is in the sense
+    /// of the original problem.
+    pub(crate) fn current_objective(&self, phase: u32) -> f64 {
+        if phase == 1 { return self.total_infeasibility(); }
+
+        let value: f64 = self.costs.iter().zip(self.values.iter())
+            .map(|(cost, value)| cost * value).sum();
+        match self.objective_kind {
+            ObjectiveKind::Minimize => value,
+            ObjectiveKind::Maximize => -value,
+        }
+    }
+
+    /// Performs the ratio test for the entering column moving in `direction`, where
+    /// `alpha` is the column in terms of the basis.  Returns the step along with how far
+    /// the entering column moves.  In phase I, basic columns outside their bounds only
+    /// limit the step when moving towards their bounds, and leave the basis on reaching
+    /// them.  With Bland's rule, ties are broken by the lowest basic column.
+    fn ratio_test(
+        &self,
+        entering: usize,
+        direction: f64,
+        alpha: &[f64],
+        phase: u32,
+        bland: bool,
+    ) -> Option<(Step, f64)> {
+        let tol = self.tolerances.pivot;
+        let feasibility = self.tolerances.feasibility;
+
+        let mut min = f64::INFINITY;
+        let mut step = None;
+        let mut leaving = None;
+        let mut best_alpha = 0.0;
+        let range = self.upper[entering] - self.lower[entering];
+        if range.is_finite() {
+            min = range;
+            step = Some(Step::Flip);
+        }
+
+        for (position, col) in self.basis.iter().enumerate() {
+            if alpha[position].abs() <= tol { continue; }
+
+            // the rate of change of the basic column as the entering column moves
+            let rate = -direction * alpha[position];
+            let value = self.values[*col];
+            let (lower, upper) = (self.lower[*col], self.upper[*col]);
+            let (ratio, to_upper) = if rate < 0.0 {
+                if phase == 1 && value > upper + feasibility {
+                    ((value - upper) / -rate, true)
+                } else if (phase == 1 && value < lower - feasibility) || !lower.is_finite() {
+                    continue;
+                } else {
+                    ((value - lower).max(0.0) / -rate, false)
+                }
+            } else if phase == 1 && value < lower - feasibility {
+                ((lower - value) / rate, false)
+            } else if (phase == 1 && value > upper + feasibility) || !upper.is_finite() {
+                continue;
+            } else {
+                ((upper - value).max(0.0) / rate, true)
+            };
+
+            let better = if bland {
+                ratio < min - tol ||
+                    (ratio <= min + tol && leaving.map_or(false, |leaving| *col < leaving))
+            } else {
+                ratio < min - tol || (ratio <= min + tol && alpha[position].abs() > best_alpha)
+            };
+
+            if better {
+                min = ratio;
+                step = Some(Step::Pivot { position, to_upper });
+                leaving = Some(*col);
+                best_alpha = alpha[position].abs();
+            }
+        }
+
+        step.map(|step| (step, min))
+    }
+}
+
+impl Pricing {
+    fn new(rule: PricingRule, revised: &Revised) -> Pricing {
+        // the initial basis is the identity, so each steepest edge weight is one plus
+        // the squared norm of the column
+        let weights = revised.columns.iter().map(|column| match rule {
+            PricingRule::SteepestEdge =>
+                1.0 + column.iter().map(|(_, value)| value * value).sum::<f64>(),
+            _ => 1.0,
+        }).collect();
+
+        Pricing { rule, weights, next: 0 }
+    }
+
+    fn score(&self, col: usize, cost: f64) -> f64 {
+        match self.rule {
+            PricingRule::Dantzig | PricingRule::Partial => cost.abs(),
+            PricingRule::SteepestEdge | PricingRule::Devex => cost * cost / self.weights[col],
+        }
+    }
+
+    /// Selects the entering column with the best score under the pricing rule, given
+    /// the prices `y`.  Columns at their lower bound are eligible with a negative
+    /// reduced cost, columns at their upper bound with a positive reduced cost, and free
+    /// columns with either.  With Bland's rule, the first eligible column is selected
+    /// instead.  Returns the column and its reduced cost.
+    fn select_column(
+        &mut self,
+        revised: &Revised,
+        y: &[f64],
+        phase: u32,
+        bland: bool,
+    ) -> Option<(usize, f64)> {
+        let num_cols = revised.columns.len();
+        let (start, segment) = match self.rule {
+            PricingRule::Partial if !bland => {
+                let segment = ((num_cols as f64).sqrt().ceil() as usize).max(PARTIAL_SEGMENT_MIN);
+                (self.next, segment)
+            },
+            _ => (0, num_cols),
+        };
+
+        let tol = revised.tolerances.optimality;
+        let mut best_score = 0.0;
+        let mut best = None;
+        for offset in 0..num_cols {
+            let col = (start + offset) % num_cols;
+            if offset > 0 && offset % segment == 0 && best.is_some() {
+                self.next = col;
+                break;
+            }
+
+            let eligible = match revised.statuses[col] {
+                BasisStatus::Basic => continue,
+                _ if revised.lower[col] == revised.upper[col] => continue,
+                status => status,
+            };
+
+            let cost = if phase == 1 { 0.0 } else { revised.costs[col] };
+            let reduced_cost = cost - revised.dot(y, col);
+            let improving = match eligible {
+                BasisStatus::AtLower => reduced_cost < -tol,
+                BasisStatus::AtUpper => reduced_cost > tol,
+                _ => reduced_cost.abs() > tol,
+            };
+            if !improving { continue; }
+
+            let score = self.score(col, reduced_cost);
+            if best.is_none() || score > best_score {
+                best = Some((col, reduced_cost));
+                best_score = score;
+                if bland { break; }
+            }
+        }
+
+        best
+    }
+
+    /// Updates the weights for the pivot of `entering` into the basis at `position`,
+    /// where `alpha` is the entering column in terms of the basis.  This is done before
+    /// the basis is updated.
+    fn update(&mut self, revised: &Revised, entering: usize, position: usize, alpha: &[f64]) {
+        if self.rule != PricingRule::Devex && self.rule != PricingRule::SteepestEdge { return; }
+
+        let pivot = alpha[position];
+        let mut unit = vec![0.0; alpha.len()];
+        unit[position] = 1.0;
+        let pivot_row = revised.factor.btran(unit);
+
+        let (weight, tau) = match self.rule {
+            PricingRule::SteepestEdge => {
+                let weight = 1.0 + alpha.iter().map(|value| value * value).sum::<f64>();
+                (weight, revised.factor.btran(alpha.to_vec()))
+            },
+            _ => (self.weights[entering], Vec::new()),
+        };
+
+        for col in 0..revised.columns.len() {
+            if col == entering || revised.statuses[col] == BasisStatus::Basic { continue; }
+
+            let ratio = revised.dot(&pivot_row, col) / pivot;
+            if ratio == 0.0 { continue; }
+
+            let other_weight = &mut self.weights[col];
+            *other_weight = match self.rule {
+                PricingRule::SteepestEdge => {
+                    let updated = *other_weight - 2.0 * ratio * revised.dot(&tau, col) +
+                        ratio * ratio * weight;
+                    updated.max(1.0 + ratio * ratio)
+                },
+                _ => other_weight.max(ratio * ratio * weight),
+            };
+        }
+
+        let leaving = revised.basis[position];
+        self.weights[leaving] = (weight / (pivot * pivot)).max(1.0);
+    }
+}
+
+pub fn solve(
+    problem: &Problem,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
+    let objective_kind = match problem.objective_kind() {
+        None => {
+            return SolverError::invalid_objective("Must set an objective for simplex.");
+        }, Some(kind) => kind,
+    };
+
+    let basis = options.basis.as_ref();
+    let mut revised = Revised::new(problem, objective_kind, options.tolerances, basis)?;
+    primal(&mut revised, SolveStats::default(), options, interrupt)
+}
+
+/// Runs the primal revised simplex method from the current basis, first finding a
+/// feasible basis if it is not feasible.  The `stats` are those of any earlier solve
+/// leading to this basis.
+pub(crate) fn primal(
+    revised: &mut Revised,
+    mut stats: SolveStats,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
+    let mut pricing = Pricing::new(options.pricing, revised);
+
+    if revised.total_infeasibility() > 0.0 {
+        info!("Performing Phase I revised simplex solve");
+        let status = simplex(revised, &mut pricing, &mut stats, options, interrupt, 1)?;
+        if status != SolveStatus::Optimal {
+            return Ok(SolveResult::new(status, None, stats));
+        }
+
+        if revised.total_infeasibility() > 0.0 {
+            info!("No feasible solution exists.");
+            return Ok(SolveResult::new(SolveStatus::Infeasible, None, stats));
+        }
+    }
+
+    info!("Performing Phase II revised simplex solve");
+    let status = simplex(revised, &mut pricing, &mut stats, options, interrupt, 2)?;
+
+    match status {
+        SolveStatus::Unbounded => Ok(SolveResult::new(status, None, stats)),
+        SolveStatus::Optimal => {
+            let mut solution = revised.produce_solution();
+            revised.produce_duals(&mut solution);
+            solution.set_basis(Basis::new(revised.statuses.clone(), revised.num_variables));
+            Ok(SolveResult::new(status, Some(solution), stats))
+        },
+        _ => {
+            info!("Stopped with a feasible solution: {:?}", status);
+            Ok(SolveResult::new(SolveStatus::Feasible, Some(revised.produce_solution()), stats))
+        },
+    }
+}
+
+impl Revised {
+    pub(crate) fn produce_solution(&self) -> Solution {
+        let coeffs = self.values[..self.num_variables].to_vec();
+        let solution = Solution::new(coeffs, Some(self.current_objective(2)));
+        info!("Solution found {:?}", solution);
+        solution
+    }
+
+    /// Computes the dual values and reduced costs from the prices of the optimal basis,
+    /// in the original objective's sense
+    fn produce_duals(&self, solution: &mut Solution) {
+        let mult = match self.objective_kind {
+            ObjectiveKind::Minimize => 1.0,
+            ObjectiveKind::Maximize => -1.0,
+        };
+
+        let y = self.prices();
+        let reduced_costs = (0..self.num_variables)
+            .map(|col| mult * self.reduced_cost(&y, col)).collect();
+        let duals = y.iter().map(|value| mult * value).collect();
+        solution.set_duals(duals, reduced_costs);
+    }
+}
+
+/// Runs the revised simplex method in the given phase until it is optimal.  Phase I
+/// minimizes the total infeasibility of the basic columns, and is optimal once it is
+/// zero or can't be reduced further.  If there is no iteration limit in the options,
+/// the iterations in this call are limited based on the size of the problem.
+fn simplex(
+    revised: &mut Revised,
+    pricing: &mut Pricing,
+    stats: &mut SolveStats,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+    phase: u32,
+) -> Result<SolveStatus> {
+    info!("Initializing revised simplex solver");
+
+    let size = (revised.columns.len() + 1) * (revised.num_constraints() + 1);
+    let max_iterations = options.iteration_limit.unwrap_or(stats.iterations + size as u64);
+
+    let mut degenerate_pivots = 0;
+    loop {
+        info!("Iteration {}", stats.iterations);
+        if phase == 1 && revised.total_infeasibility() == 0.0 {
+            info!("Found a feasible basis");
+            break;
+        }
+
+        if stats.iterations >= max_iterations {
+            warn!("Warning.  Failed to find solution after {} iterations", stats.iterations);
+            return Ok(SolveStatus::IterationLimit);
+        }
+
+        if let Some(status) = interrupt.check() {
+            warn!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            return Ok(status);
+        }
+
+        let bland = degenerate_pivots >= DEGENERATE_PIVOT_LIMIT;
+        if degenerate_pivots == DEGENERATE_PIVOT_LIMIT {
+            info!("Switching to Bland's rule after {} degenerate pivots", degenerate_pivots);
+        }
+
+        let y = revised.factor.btran(revised.basic_costs(phase));
+        let (entering, reduced_cost) = match pricing.select_column(revised, &y, phase, bland) {
+            None => {
+                info!("No available entering columns - solution is optimal");
+                break;
+            }, Some(selected) => selected,
+        };
+        let direction = if reduced_cost < 0.0 { 1.0 } else { -1.0 };
+        info!("Selected entering column {}", entering);
+
+        let alpha = revised.factor.ftran(revised.dense_column(entering));
+        let (step, ratio) = match revised.ratio_test(entering, direction, &alpha, phase, bland) {
+            None if phase == 1 => {
+                return SolverError::unable_to_solve("No leaving column found in phase I.");
+            },
+            None => {
+                warn!("Unable to find a leaving column.  function is unbounded.");
+                return Ok(SolveStatus::Unbounded);
+            },
+            Some(step) => step,
+        };
+
+        if ratio <= revised.tolerances.pivot {
+            degenerate_pivots += 1;
+        } else {
+            degenerate_pivots = 0;
+        }
+
+        revised.advance(entering, &alpha, direction * ratio);
+
+        match step {
+            Step::Flip => {
+                info!("Entering column reaches its bound");
+                revised.statuses[entering] = if direction > 0.0 {
+                    revised.values[entering] = revised.upper[entering];
+                    BasisStatus::AtUpper
+                } else {
+                    revised.values[entering] = revised.lower[entering];
+                    BasisStatus::AtLower
+                };
+            },
+            Step::Pivot { position, to_upper } => {
+                pricing.update(revised, entering, position, &alpha);
+                revised.replace(position, entering, &alpha, to_upper)?;
+            }
+        }
+
+        stats.iterations += 1;
+        let progress = Progress::Simplex {
+            iteration: stats.iterations,
+            objective: revised.current_objective(phase),
+            phase,
+        };
+        if let Some(status) = interrupt.report(progress) {
+            info!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            return Ok(status);
+        }
+    }
+
+    // recompute the basic values from a fresh factorization, removing any drift
+    revised.refactor()?;
+
+    info!("Revised simplex solve complete.");
+    Ok(SolveStatus::Optimal)
+}
@@ -0,0 +1,86 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use crate::Variable;
+
+/// How far a single value of the problem can move while the optimal basis stays
+/// optimal.  Within this range, dual values and reduced costs are unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ranging {
+    value: f64,
+    decrease: f64,
+    increase: f64,
+    degenerate: bool,
+}
+
+impl Ranging {
+    pub(crate) fn new(value: f64, decrease: f64, increase: f64, degenerate: bool) -> Ranging {
+        Ranging { value, decrease, increase, degenerate }
+    }
+
+    /// The current value
+    pub fn value(&self) -> f64 { self.value }
+
+    /// The amount the value can decrease by, which may be infinite
+    pub fn allowable_decrease(&self) -> f64 { self.decrease }
+
+    /// The amount the value can increase by, which may be infinite
+    pub fn allowable_increase(&self) -> f64 { self.increase }
+
+    /// The smallest value for which the basis remains optimal
+    pub fn lower(&self) -> f64 { self.value - self.decrease }
+
+    /// The largest value for which the basis remains optimal
+    pub fn upper(&self) -> f64 { self.value + self.increase }
+
+    /// Whether the range is empty in at least one direction because the optimal
+    /// solution is degenerate.  In this case, the value may still be able to move
+    /// without changing the optimal objective, but only with a change of basis.
+    pub fn is_degenerate(&self) -> bool { self.degenerate }
+}
+
+/// Sensitivity analysis around an optimal basis of a linear program, covering each
+/// objective coefficient and each constraint constant.  Each range is computed with
+/// all other values held fixed.
+#[derive(Debug, Clone)]
+pub struct Sensitivity {
+    costs: Vec<Ranging>,
+    constants: Vec<Ranging>,
+}
+
+impl Sensitivity {
+    pub(crate) fn new(costs: Vec<Ranging>, constants: Vec<Ranging>) -> Sensitivity {
+        Sensitivity { costs, constants }
+    }
+
+    /// The ranging of the objective coefficient of each variable
+    pub fn costs(&self) -> &[Ranging] { &self.costs }
+
+    pub fn cost(&self, index: usize) -> &Ranging { &self.costs[index] }
+
+    pub fn cost_of(&self, variable: Variable) -> &Ranging { &self.costs[variable.index()] }
+
+    /// The ranging of the constant of each constraint.  For a ranged constraint, both
+    /// its lower and upper constants move together.
+    pub fn constants(&self) -> &[Ranging] { &self.constants }
+
+    pub fn constant(&self, index: usize) -> &Ranging { &self.constants[index] }
+
+    /// Whether any of the ranges are degenerate
+    pub fn is_degenerate(&self) -> bool {
+        self.costs.iter().chain(self.constants.iter()).any(|ranging| ranging.degenerate)
+    }
+}
@@ -19,115 +19,938 @@ use std::f64;
 use log::{debug, info, warn};
 
 use crate::{ConstraintKind, Row, Col, Matrix, Problem, Solution, ObjectiveKind,
-    Result, SolverError};
+    Result, SolverError, SolveResult, SolveStatus, SolveStats, Sensitivity, Ranging, SolverOptions,
+    Tolerances, Progress, PricingRule};
+use crate::options::Interrupt;
 
-const OPTIMALITY_TOL: f64 = 1e-6;
+/// The first column of the tableau holding a problem variable.  The columns before it
+/// hold the phase I and phase II objective values.
+const FIRST_COL: usize = 2;
 
-pub fn solve(problem: &Problem) -> Result<Solution> {
-    let num_variables = problem.num_variables();
+/// The number of consecutive degenerate pivots after which Bland's rule is used to
+/// prevent cycling
+pub(crate) const DEGENERATE_PIVOT_LIMIT: u32 = 10;
+
+/// The smallest number of columns priced at a time with partial pricing
+pub(crate) const PARTIAL_SEGMENT_MIN: usize = 8;
+
+/// The first row of the tableau holding a constraint.  The rows before it hold the
+/// phase I and phase II objectives.
+const FIRST_ROW: usize = 2;
+
+/// Describes how a tableau column relates to its underlying variable.  Every column is
+/// transformed so that it is zero while non-basic; the underlying variable's value is
+/// `offset + sign * x`, where `x` is the value in the tableau.  Unless the column
+/// is free, `x` must lie within `[0, range]`.
+#[derive(Debug, Clone, Copy)]
+struct Column {
+    offset: f64,
+    sign: f64,
+    range: f64,
+    free: bool,
+}
+
+impl Column {
+    fn new(lower: f64, upper: f64) -> Column {
+        if lower.is_finite() {
+            Column { offset: lower, sign: 1.0, range: upper - lower, free: false }
+        } else if upper.is_finite() {
+            Column { offset: upper, sign: -1.0, range: f64::INFINITY, free: false }
+        } else {
+            Column { offset: 0.0, sign: 1.0, range: f64::INFINITY, free: true }
+        }
+    }
+
+    fn is_fixed(&self) -> bool {
+        !self.free && self.range == 0.0
+    }
+
+    fn value(&self, x: f64) -> f64 {
+        self.offset + self.sign * x
+    }
+
+    /// The lower and upper bounds of the underlying variable
+    fn bounds(&self) -> (f64, f64) {
+        if self.free {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        } else if self.sign > 0.0 {
+            (self.offset, self.offset + self.range)
+        } else {
+            (self.offset - self.range, self.offset)
+        }
+    }
+}
+
+/// The tableau for the bounded variable simplex method.  The first two rows hold the
+/// phase I and phase II objectives, followed by one row per constraint.  The columns
+/// hold the two objective values, one column per problem variable, one logical
+/// column per constraint, any artificial columns needed for an initial feasible basis,
+/// and finally the values of the basic variables.  The objective is always minimized;
+/// maximization problems have their objective negated.
+struct Tableau {
+    matrix: Matrix,
+    basis: Vec<Col>,
+    columns: Vec<Column>,
+    num_variables: usize,
+    num_constraints: usize,
+    num_artificial: usize,
+    objective_kind: ObjectiveKind,
+    tolerances: Tolerances,
+}
+
+/// The status of a variable or logical column in a basis
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(crate) enum BasisStatus {
+    Basic,
+    AtLower,
+    AtUpper,
+
+    /// A non-basic free variable, at zero
+    Free,
+}
+
+/// An optimal basis of a linear program, as found by the simplex method.  This holds
+/// the status of each problem variable, followed by the status of the logical
+/// variable of each constraint.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Basis {
+    statuses: Vec<BasisStatus>,
+    num_variables: usize,
+}
+
+impl Basis {
+    pub(crate) fn new(statuses: Vec<BasisStatus>, num_variables: usize) -> Basis {
+        Basis { statuses, num_variables }
+    }
+
+    pub(crate) fn statuses(&self) -> &[BasisStatus] {
+        &self.statuses
+    }
+
+    /// The number of problem variables in the problem this basis is for
+    pub fn num_variables(&self) -> usize {
+        self.num_variables
+    }
+
+    /// The number of constraints in the problem this basis is for
+    pub fn num_constraints(&self) -> usize {
+        self.statuses.len() - self.num_variables
+    }
+}
+
+/// The state of the pricing rule over the course of a simplex solve
+struct Pricing {
+    rule: PricingRule,
+
+    /// The Devex reference weight of each column
+    weights: Vec<f64>,
+
+    /// The column to start the next segment at, for partial pricing
+    next: usize,
+}
+
+impl Pricing {
+    fn new(rule: PricingRule, tableau: &Tableau) -> Pricing {
+        Pricing {
+            rule,
+            weights: vec![1.0; tableau.matrix.last_col().index()],
+            next: FIRST_COL,
+        }
+    }
+
+    /// Scores an eligible column with objective row entry `value`, higher being better
+    fn score(&self, tableau: &Tableau, col: Col, value: f64) -> f64 {
+        match self.rule {
+            PricingRule::Dantzig | PricingRule::Partial => value,
+            PricingRule::SteepestEdge => {
+                let matrix = &tableau.matrix;
+                let norm: f64 = matrix.rows_from(Row::new(FIRST_ROW))
+                    .map(|row| matrix.value(row, col).powi(2)).sum();
+                value * value / (1.0 + norm)
+            },
+            PricingRule::Devex => value * value / self.weights[col.index()],
+        }
+    }
+
+    /// Updates the reference weights for the pivot on `row` and `col`, before it is
+    /// performed
+    fn update(&mut self, tableau: &Tableau, row: Row, col: Col) {
+        if self.rule != PricingRule::Devex { return; }
+
+        let matrix = &tableau.matrix;
+        let pivot = matrix.value(row, col);
+        let weight = self.weights[col.index()];
+        for other in matrix.cols_range(Col::new(FIRST_COL), matrix.last_col()) {
+            if other == col || tableau.is_basic(other) { continue; }
+
+            let ratio = matrix.value(row, other) / pivot;
+            let other_weight = &mut self.weights[other.index()];
+            *other_weight = other_weight.max(ratio * ratio * weight);
+        }
+
+        let leaving = tableau.basis[row.index() - FIRST_ROW];
+        self.weights[leaving.index()] = (weight / (pivot * pivot)).max(1.0);
+    }
+}
+
+#[derive(Debug)]
+enum Step {
+    /// The entering column reaches its opposite bound before any basic variable
+    Flip,
+
+    /// The basic variable in the row leaves the basis.  If `to_upper` is set, it
+    /// leaves at its upper bound
+    Pivot { row: Row, to_upper: bool },
+}
+
+impl Tableau {
+    fn new(
+        problem: &Problem,
+        objective_kind: ObjectiveKind,
+        tolerances: Tolerances,
+    ) -> Result<Tableau> {
+        let num_variables = problem.num_variables();
+        let num_constraints = problem.num_constraints();
+        info!("Set up simplex problem with {} constraints and {} variables",
+              num_constraints, num_variables);
+
+        let mut columns: Vec<Column> = problem.variables().map(|variable| {
+            let (lower, upper) = problem.bounds(*variable);
+            Column::new(lower, upper)
+        }).collect();
+
+        // each constraint a.x <= b is written as a.x + s = b, with bounds on s.  Ranged
+        // constraints use the upper constant, with s bounded by the width of the range
+        for constraint in problem.constraints() {
+            let (lower, upper) = match constraint.kind() {
+                ConstraintKind::LessThanOrEqualTo => (0.0, f64::INFINITY),
+                ConstraintKind::GreaterThanOrEqualTo => (f64::NEG_INFINITY, 0.0),
+                ConstraintKind::EqualTo => (0.0, 0.0),
+                ConstraintKind::Range(lower) => (0.0, constraint.constant() - lower),
+            };
+            columns.push(Column::new(lower, upper));
+        }
+
+        // the constant of each constraint row, with the offsets of its columns moved
+        // across to the right hand side
+        let constants: Vec<f64> = problem.constraints().iter().enumerate().map(|(i, constraint)| {
+            let constant = constraint.expr().iter().fold(constraint.constant(),
+                |constant, (index, value)| constant - value * columns[*index as usize].offset);
+            constant - columns[num_variables + i].offset
+        }).collect();
+
+        // use each logical as the initial basic variable of its row where it is feasible,
+        // otherwise add an artificial variable.  Each row is multiplied by the sign of its
+        // logical, and rows with artificials are negated as needed so the artificial
+        // starts non-negative
+        let first_artificial = columns.len() + FIRST_COL;
+        let mut basis = Vec::new();
+        let mut artificial_rows = Vec::new();
+        let mut row_mults = Vec::new();
+        for (i, constant) in constants.iter().enumerate() {
+            let logical = columns[num_variables + i];
+            let value = logical.sign * constant;
+            if logical.free || (value >= 0.0 && value <= logical.range) {
+                basis.push(Col::new(FIRST_COL + num_variables + i));
+                row_mults.push(logical.sign);
+            } else {
+                basis.push(Col::new(first_artificial + artificial_rows.len()));
+                artificial_rows.push(Row::new(i + FIRST_ROW));
+                row_mults.push(if value < 0.0 { -logical.sign } else { logical.sign });
+            }
+        }
+
+        let num_artificial = artificial_rows.len();
+        let height = num_constraints + FIRST_ROW;
+        let width = first_artificial + num_artificial + 1;
+        let mut entries = vec![(0, 0, 1.0), (1, 1, 1.0)];
+
+        for (i, constraint) in problem.constraints().iter().enumerate() {
+            for (index, value) in constraint.expr().iter() {
+                let column = &columns[*index as usize];
+                entries.push((i + FIRST_ROW, FIRST_COL + *index as usize, value * column.sign));
+            }
+
+            let logical = columns[num_variables + i];
+            entries.push((i + FIRST_ROW, FIRST_COL + num_variables + i, logical.sign));
+            entries.push((i + FIRST_ROW, width - 1, constants[i]));
+        }
+
+        let mult = match objective_kind {
+            ObjectiveKind::Minimize => 1.0,
+            ObjectiveKind::Maximize => -1.0,
+        };
+        if let Some(expr) = problem.objective_expr() {
+            let mut constant = 0.0;
+            for (index, value) in expr.iter() {
+                let column = &columns[*index as usize];
+                entries.push((1, FIRST_COL + *index as usize, -mult * value * column.sign));
+                constant += mult * value * column.offset;
+            }
+            entries.push((1, width - 1, constant));
+        }
+
+        // the tableau fills in as it is pivoted, so it is built dense straight from the
+        // entries
+        let mut matrix = Matrix::from_entries(width, height, entries)?;
+        for (i, row_mult) in row_mults.into_iter().enumerate() {
+            if row_mult < 0.0 {
+                matrix.multiply_row(Row::new(i + FIRST_ROW), -1.0);
+            }
+        }
+
+        // the phase I objective is the sum of the artificial rows, without the
+        // artificial columns themselves
+        for (k, row) in artificial_rows.into_iter().enumerate() {
+            matrix.add_row(row, Row::new(0), 1.0);
+            matrix.set_value(row, Col::new(first_artificial + k), 1.0);
+        }
+
+        for _ in 0..num_artificial {
+            columns.push(Column { offset: 0.0, sign: 1.0, range: f64::INFINITY, free: false });
+        }
+
+        info!("Set up {} artificial variables", num_artificial);
+        debug!("{:?}", matrix);
+
+        Ok(Tableau {
+            matrix,
+            basis,
+            columns,
+            num_variables,
+            num_constraints,
+            num_artificial,
+            objective_kind,
+            tolerances,
+        })
+    }
+
+    /// Sets up the phase II tableau for the given basis of the problem.  Returns an
+    /// error if the basis does not match the problem or is singular.
+    fn from_basis(
+        problem: &Problem,
+        objective_kind: ObjectiveKind,
+        basis: &Basis,
+        tolerances: Tolerances,
+    ) -> Result<Tableau> {
+        let mut tableau = Tableau::new(problem, objective_kind, tolerances)?;
+        if basis.num_variables != tableau.num_variables ||
+            basis.num_constraints() != tableau.num_constraints {
+            return SolverError::invalid_solution(format!("Basis has {} variables and {} \
+                constraints, but the problem has {} and {}.", basis.num_variables,
+                basis.num_constraints(), tableau.num_variables, tableau.num_constraints));
+        }
+
+        tableau.drop_phase_one()?;
+
+        let mut assigned = vec![false; tableau.num_constraints];
+        for (index, status) in basis.statuses.iter().enumerate() {
+            if *status != BasisStatus::Basic { continue; }
+
+            let col = Col::new(FIRST_COL + index);
+            let mut best = None;
+            let mut best_val = tableau.tolerances.pivot;
+            for (i, done) in assigned.iter().enumerate() {
+                let value = tableau.matrix.value(Row::new(i + FIRST_ROW), col).abs();
+                if !done && value > best_val {
+                    best = Some(i);
+                    best_val = value;
+                }
+            }
+
+            match best {
+                None => return SolverError::invalid_solution("Basis is singular."),
+                Some(i) => {
+                    tableau.pivot(Row::new(i + FIRST_ROW), col);
+                    assigned[i] = true;
+                }
+            }
+        }
+
+        if assigned.iter().any(|done| !done) {
+            return SolverError::invalid_solution("Basis has too few basic columns.");
+        }
+
+        for (index, status) in basis.statuses.iter().enumerate() {
+            let col = Col::new(FIRST_COL + index);
+            let column = *tableau.column(col);
+            let flip = match status {
+                BasisStatus::Basic | BasisStatus::Free => false,
+                BasisStatus::AtLower => column.sign < 0.0,
+                BasisStatus::AtUpper => column.sign > 0.0,
+            };
+
+            if flip {
+                if column.free || !column.range.is_finite() {
+                    return SolverError::invalid_solution(format!("Basis places column {} at \
+                        an infinite bound.", index));
+                }
+                tableau.flip(col);
+            }
+        }
+
+        Ok(tableau)
+    }
+
+    /// Fixes the artificial columns at zero and moves the view to the phase II objective,
+    /// for starting from a basis other than the initial one
+    fn drop_phase_one(&mut self) -> Result<()> {
+        for column in self.columns.iter_mut().skip(self.num_variables + self.num_constraints) {
+            column.range = 0.0;
+        }
+        self.matrix.sub(1, 1)
+    }
+
+    /// Whether every basic variable is within its bounds
+    fn is_feasible(&self) -> bool {
+        let last_col = self.matrix.last_col();
+        let tol = self.tolerances.feasibility;
+        self.basis.iter().enumerate().all(|(i, col)| {
+            let value = self.matrix.value(Row::new(i + FIRST_ROW), last_col);
+            let column = self.column(*col);
+            column.free || (value >= -tol && value <= column.range + tol)
+        })
+    }
+
+    /// Returns the current basis, or `None` if an artificial variable is still basic
+    fn current_basis(&self) -> Option<Basis> {
+        if self.basis.iter().any(|col| *col >= self.first_artificial()) {
+            return None;
+        }
+
+        let statuses = self.matrix.cols_range(Col::new(FIRST_COL), self.first_artificial())
+            .map(|col| {
+                let column = self.column(col);
+                if self.is_basic(col) {
+                    BasisStatus::Basic
+                } else if column.free {
+                    BasisStatus::Free
+                } else if column.sign > 0.0 {
+                    BasisStatus::AtLower
+                } else {
+                    BasisStatus::AtUpper
+                }
+            }).collect();
+
+        Some(Basis { statuses, num_variables: self.num_variables })
+    }
+
+    /// The value of the objective currently being minimized.  For the phase II
+    /// objective, this is in the sense of the original problem.
+    fn current_objective(&self) -> f64 {
+        let value = self.matrix.value(self.matrix.first_row(), self.matrix.last_col());
+        match (self.matrix.first_row().index(), self.objective_kind) {
+            (1, ObjectiveKind::Maximize) => -value,
+            _ => value,
+        }
+    }
+
+    fn basic_row(&self, col: Col) -> Option<Row> {
+        self.basis.iter().position(|basic| *basic == col).map(|i| Row::new(i + FIRST_ROW))
+    }
+
+    /// The reduced cost of the column in the phase II objective
+    fn reduced_cost(&self, col: Col) -> f64 {
+        -self.matrix.value(Row::new(1), col)
+    }
+
+    fn column(&self, col: Col) -> &Column {
+        &self.columns[col.index() - FIRST_COL]
+    }
+
+    fn column_mut(&mut self, col: Col) -> &mut Column {
+        &mut self.columns[col.index() - FIRST_COL]
+    }
+
+    fn first_artificial(&self) -> Col {
+        Col::new(FIRST_COL + self.num_variables + self.num_constraints)
+    }
+
+    fn is_basic(&self, col: Col) -> bool {
+        self.basis.contains(&col)
+    }
+
+    /// Substitutes `range - x` for the column `x`, moving it from one of its bounds to
+    /// the other
+    fn flip(&mut self, col: Col) {
+        let column = *self.column(col);
+        debug!("Flipping column {:?} to its opposite bound", col);
+
+        let last_col = self.matrix.last_col();
+        for row in self.matrix.rows() {
+            let value = self.matrix.value(row, col);
+            if value == 0.0 { continue; }
+
+            let constant = self.matrix.value(row, last_col) - column.range * value;
+            self.matrix.set_value(row, last_col, constant);
+            self.matrix.set_value(row, col, -value);
+        }
+
+        let column = self.column_mut(col);
+        column.offset += column.sign * column.range;
+        column.sign = -column.sign;
+    }
+
+    /// Substitutes `-x` for the free column `x`
+    fn negate(&mut self, col: Col) {
+        for row in self.matrix.rows() {
+            let value = self.matrix.value(row, col);
+            self.matrix.set_value(row, col, -value);
+        }
+
+        let column = self.column_mut(col);
+        column.sign = -column.sign;
+    }
+
+    /// Substitutes `amount + x` for the column `x`, so that the column is zero where it
+    /// was `amount` before
+    fn shift(&mut self, col: Col, amount: f64) {
+        let last_col = self.matrix.last_col();
+        for row in self.matrix.rows() {
+            let value = self.matrix.value(row, col);
+            if value == 0.0 { continue; }
+
+            let constant = self.matrix.value(row, last_col) - amount * value;
+            self.matrix.set_value(row, last_col, constant);
+        }
+
+        let column = self.column_mut(col);
+        column.offset += column.sign * amount;
+        column.range -= amount;
+    }
+
+    /// Sets up the non-basic column, which is zero at a value between the given bounds,
+    /// to move towards the upper bound if `increase` is set, or else the lower bound
+    fn orient(&mut self, col: Col, (lower, upper): (f64, f64), increase: bool) {
+        if (self.column(col).sign > 0.0) != increase {
+            self.negate(col);
+        }
+
+        let column = self.column_mut(col);
+        column.range = if increase { upper - column.offset } else { column.offset - lower };
+    }
+
+    /// Makes the basic column in the row start from one of its bounds again after it was
+    /// shifted, so that its range is the gap between the bounds
+    fn restore(&mut self, row: Row, col: Col, (lower, upper): (f64, f64)) {
+        if self.column(col).free { return; }
+
+        let increase = lower.is_finite();
+        if (self.column(col).sign > 0.0) != increase {
+            self.negate(col);
+            self.matrix.multiply_row(row, -1.0);
+        }
+
+        let column = *self.column(col);
+        let bound = if increase { lower } else { upper };
+        self.shift(col, (bound - column.offset) * column.sign);
+        self.column_mut(col).range = upper - lower;
+    }
+
+    /// Moves the non-basic column, which is zero at a value strictly between its bounds,
+    /// until either it reaches a bound or a basic variable reaches one, in which case
+    /// the column enters the basis.  The direction not increasing the objective is tried
+    /// first.  Returns false if the column can move without limit in both directions.
+    fn push(&mut self, col: Col, bounds: (f64, f64)) -> bool {
+        let increase = (self.reduced_cost(col) <= 0.0) == (self.column(col).sign > 0.0);
+        for increase in [increase, !increase].iter() {
+            self.orient(col, bounds, *increase);
+            match select_pivot_row(self, col, false) {
+                None => continue,
+                Some((Step::Flip, _)) => {
+                    self.flip(col);
+                    self.column_mut(col).range = bounds.1 - bounds.0;
+                },
+                Some((Step::Pivot { row, to_upper }, _)) => {
+                    let leaving = self.basis[row.index() - FIRST_ROW];
+                    self.pivot(row, col);
+                    if to_upper {
+                        self.flip(leaving);
+                    }
+                    self.restore(row, col, bounds);
+                },
+            }
+
+            return true;
+        }
+
+        false
+    }
+
+    fn pivot(&mut self, row: Row, col: Col) {
+        simplex_pivot(&mut self.matrix, row, col);
+        self.basis[row.index() - FIRST_ROW] = col;
+    }
+
+    /// Pivots any artificial variables left in the basis at zero level after phase I out
+    /// of the basis, and then fixes all artificial variables at zero.  Rows where the
+    /// artificial variable can't be removed are redundant.
+    fn remove_artificial(&mut self) {
+        let first_artificial = self.first_artificial();
+        for i in 0..self.num_constraints {
+            if self.basis[i] < first_artificial { continue; }
+
+            let row = Row::new(i + FIRST_ROW);
+            let pivot_col = self.matrix.cols_range(Col::new(FIRST_COL), first_artificial)
+                .find(|col| self.matrix.value(row, *col).abs() > self.tolerances.pivot);
+
+            match pivot_col {
+                None => info!("Row {:?} is redundant", row),
+                Some(col) => self.pivot(row, col),
+            }
+        }
+
+        for column in self.columns.iter_mut().skip(self.num_variables + self.num_constraints) {
+            column.range = 0.0;
+        }
+    }
+
+    /// Produces the result of a phase II solve that finished with the given status
+    fn into_result(self, status: SolveStatus, stats: SolveStats) -> SolveResult {
+        // the basis remains feasible in phase II, so it is still a usable solution if the
+        // solve stops early
+        match status {
+            SolveStatus::Unbounded => SolveResult::new(status, None, stats),
+            SolveStatus::Optimal => {
+                let mut solution = self.produce_solution();
+                self.produce_duals(&mut solution);
+                if let Some(basis) = self.current_basis() {
+                    solution.set_basis(basis);
+                }
+                SolveResult::new(status, Some(solution), stats)
+            },
+            _ => {
+                info!("Stopped with a feasible solution: {:?}", status);
+                SolveResult::new(SolveStatus::Feasible, Some(self.produce_solution()), stats)
+            },
+        }
+    }
+
+    fn produce_solution(&self) -> Solution {
+        let mut values = vec![0.0; self.columns.len()];
+        for (i, col) in self.basis.iter().enumerate() {
+            values[col.index() - FIRST_COL] =
+                self.matrix.value(Row::new(i + FIRST_ROW), self.matrix.last_col());
+        }
+
+        let coeffs = values.iter().zip(self.columns.iter()).take(self.num_variables)
+            .map(|(value, column)| column.value(*value)).collect();
+
+        let solution = Solution::new(coeffs, Some(self.current_objective()));
+        info!("Solution found {:?}", solution);
+        solution
+    }
+
+    /// Reads the dual values and reduced costs out of the phase II objective row of an
+    /// optimal tableau.  Each is the rate of change of the objective with respect to the
+    /// constraint constant or the variable, in the original objective's sense.
+    fn produce_duals(&self, solution: &mut Solution) {
+        let mult = match self.objective_kind {
+            ObjectiveKind::Minimize => 1.0,
+            ObjectiveKind::Maximize => -1.0,
+        };
+
+        // the objective row holds the negated reduced costs of the transformed columns
+        let mut reduced_costs = self.matrix.cols_range(Col::new(FIRST_COL), self.first_artificial())
+            .map(|col| -mult * self.column(col).sign * self.matrix.value(Row::new(1), col));
+
+        let variables = reduced_costs.by_ref().take(self.num_variables).collect();
+
+        // each logical column is a unit column in its constraint row with zero cost, so
+        // its reduced cost is the negated dual
+        let duals = reduced_costs.map(|cost| -cost).collect();
+
+        solution.set_duals(duals, variables);
+    }
+}
+
+pub fn solve(
+    problem: &Problem,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
     let objective_kind = match problem.objective_kind() {
         None => {
             return SolverError::invalid_objective("Must set an objective for simplex.");
         }, Some(kind) => kind,
     };
 
-    let (mut matrix, num_artificial) = setup_matrix(problem)?;
-    info!("Set up {} artificial variables", num_artificial);
+    let mut tableau = Tableau::new(problem, objective_kind, options.tolerances)?;
+    let mut stats = SolveStats::default();
 
-    info!("Performing Phase I simplex solve");
-    let phase1 = simplex(&mut matrix, num_variables, 2, ObjectiveKind::Minimize)?;
+    if tableau.num_artificial > 0 {
+        info!("Performing Phase I simplex solve");
+        let status = simplex(&mut tableau, &mut stats, options, interrupt, 1);
+        if status != SolveStatus::Optimal {
+            return Ok(SolveResult::new(status, None, stats));
+        }
 
-    if let Some(solution) = phase1.objective() {
-        if solution.abs() > OPTIMALITY_TOL {
-            return SolverError::infeasible("No feasible solution exists.");
+        let infeasibility = tableau.matrix.value(Row::new(0), tableau.matrix.last_col());
+        if infeasibility.abs() > options.tolerances.feasibility {
+            info!("No feasible solution exists.");
+            return Ok(SolveResult::new(SolveStatus::Infeasible, None, stats));
         }
+
+        tableau.remove_artificial();
     }
 
     info!("Performing Phase II simplex solve");
-    matrix.sub(1, 1);
-    let last_col = matrix.last_col();
-    for row in matrix.rows() {
-        for col in matrix.cols_range(last_col - num_artificial, last_col) {
-            matrix.set_value(row, col, 0.0);
-        }
-    }
+    tableau.matrix.sub(1, 1)?;
 
     info!("Phase 2 Matrix:");
-    info!("{:?}", matrix);
-    simplex(&mut matrix, num_variables, 1, objective_kind)
+    info!("{:?}", tableau.matrix);
+    let status = simplex(&mut tableau, &mut stats, options, interrupt, 2);
+
+    Ok(tableau.into_result(status, stats))
 }
 
-pub fn setup_matrix(problem: &Problem) -> Result<(Matrix, usize)> {
-    info!("Set up simplex problem with {} constraints and {} variables",
-          problem.num_constraints(), problem.num_variables());
+/// Moves an optimal solution that need not be basic, such as one found by the interior
+/// point method, to an optimal basic solution.  Each variable strictly between its
+/// bounds is pushed to one of them, or into the basis if a basic variable reaches its
+/// bound first, giving a feasible basis.  The simplex method then pivots away any
+/// remaining dual infeasibility.  The solution's reduced costs, if it has them, help
+/// decide which variables are already at a bound.
+pub(crate) fn crossover(
+    problem: &Problem,
+    point: Solution,
+    mut stats: SolveStats,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+) -> Result<SolveResult> {
+    let objective_kind = match problem.objective_kind() {
+        None => {
+            return SolverError::invalid_objective("Must set an objective for crossover.");
+        }, Some(kind) => kind,
+    };
+
+    let mult = match objective_kind {
+        ObjectiveKind::Minimize => 1.0,
+        ObjectiveKind::Maximize => -1.0,
+    };
+
+    // start with every logical basic, and every variable at the value in the point
+    let tolerances = options.tolerances;
+    let mut tableau = Tableau::new(problem, objective_kind, tolerances)?;
+    tableau.drop_phase_one()?;
+    for i in 0..tableau.num_constraints {
+        let logical = Col::new(FIRST_COL + tableau.num_variables + i);
+        if tableau.basis[i] != logical {
+            tableau.pivot(Row::new(i + FIRST_ROW), logical);
+        }
+    }
+
+    let mut superbasic = Vec::new();
+    for index in 0..tableau.num_variables {
+        let col = Col::new(FIRST_COL + index);
+        let column = *tableau.column(col);
+        if column.is_fixed() { continue; }
+
+        // a variable is taken to be at a bound when it is closer to the bound than its
+        // reduced cost is to zero
+        let x = (point.value(index) - column.offset) * column.sign;
+        let cost = point.reduced_costs().map_or(0.0, |costs| mult * costs[index] * column.sign);
+        let tol = tolerances.feasibility;
+        if column.free {
+            if x.abs() <= tol { continue; }
+        } else if x <= tol || x < cost {
+            continue;
+        } else if column.range - x <= tol || column.range - x < -cost {
+            tableau.flip(col);
+            continue;
+        }
 
-    let height = problem.num_constraints() + 2;
-    let width = problem.num_variables() + problem.num_constraints() + 3;
-    let mut coeffs = vec![0.0;width*height];
+        let bounds = column.bounds();
+        tableau.shift(col, x);
+        superbasic.push((col, bounds));
+    }
 
-    let mut row = 2;
-    for constraint in problem.constraints().iter() {
-        let neg = if constraint.constant() < 0.0 { -1.0 } else { 1.0 };
+    info!("Pushing {} variables to their bounds", superbasic.len());
+    for (col, bounds) in superbasic {
+        if let Some(status) = interrupt.check() {
+            warn!("Stopping crossover after {} iterations: {:?}", stats.iterations, status);
+            return Ok(SolveResult::new(SolveStatus::Feasible, Some(point), stats));
+        }
 
-        for (index, value) in constraint.expr().iter() {
-            coeffs[2 + *index as usize + row * width] = *value * neg;
+        if !tableau.push(col, bounds) {
+            warn!("Unable to push free column {:?} to a bound", col);
         }
-        coeffs[width - 1 + row * width] = constraint.constant() * neg;
+        stats.iterations += 1;
+    }
+
+    let basis = tableau.current_basis();
+    let tableau = basis.and_then(|basis| {
+        Tableau::from_basis(problem, objective_kind, &basis, tolerances).ok()
+    });
+    let mut tableau = match tableau {
+        Some(tableau) if tableau.is_feasible() => tableau,
+        _ => {
+            warn!("Crossover did not find a feasible basis.  Solving from the start.");
+            let mut result = solve(problem, options, interrupt)?;
+            result.stats_mut().add(&stats);
+            return Ok(result);
+        }
+    };
+
+    info!("Performing simplex solve from the crossover basis");
+    let status = simplex(&mut tableau, &mut stats, options, interrupt, 2);
+    Ok(tableau.into_result(status, stats))
+}
 
-        row += 1;
+/// Computes objective coefficient and constraint constant ranging for an optimal basis
+/// of the problem
+pub(crate) fn sensitivity(
+    problem: &Problem,
+    basis: &Basis,
+    tolerances: &Tolerances,
+) -> Result<Sensitivity> {
+    let (objective, objective_kind) = match problem.objective() {
+        None => return SolverError::invalid_objective("Must set an objective for sensitivity."),
+        Some(objective) => objective,
+    };
+
+    let tableau = Tableau::from_basis(problem, objective_kind, basis, *tolerances)?;
+    let matrix = &tableau.matrix;
+    let last_col = matrix.last_col();
+
+    if !tableau.is_feasible() {
+        return SolverError::invalid_solution("Basis is not feasible.");
     }
 
-    coeffs[0] = 1.0;
-    if let Some(objective) = problem.objective_expr() {
-        let row = 1;
-        for (index, value) in objective.iter(){
-            coeffs[2 + *index as usize + row * width] = *value * -1.0;
+    let nonbasic: Vec<Col> = matrix.cols_range(Col::new(FIRST_COL), last_col)
+        .filter(|col| !tableau.is_basic(*col) && !tableau.column(*col).is_fixed())
+        .collect();
+    for col in nonbasic.iter() {
+        let cost = tableau.reduced_cost(*col);
+        let tol = tolerances.optimality;
+        if cost < -tol || (tableau.column(*col).free && cost > tol) {
+            return SolverError::invalid_solution("Basis is not optimal.");
         }
-        coeffs[1 + row * width] = 1.0;
     }
 
-    let mut matrix = Matrix::new(width, height, coeffs);
-    info!("Set up initial problem.");
-    debug!("{:?}", matrix);
+    let mult = match objective_kind {
+        ObjectiveKind::Minimize => 1.0,
+        ObjectiveKind::Maximize => -1.0,
+    };
+
+    let costs = (0..tableau.num_variables).map(|index| {
+        let col = Col::new(FIRST_COL + index);
+        let column = tableau.column(col);
+
+        // the range of changes to the minimized cost of the tableau column
+        let (lower, upper) = if column.is_fixed() {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        } else if let Some(row) = tableau.basic_row(col) {
+            // changing the cost of a basic column changes the reduced cost of each
+            // non-basic column in proportion to its entry in the row
+            let mut lower = f64::NEG_INFINITY;
+            let mut upper = f64::INFINITY;
+            for nonbasic_col in nonbasic.iter() {
+                let value = matrix.value(row, *nonbasic_col);
+                if value.abs() <= tolerances.pivot { continue; }
+
+                if tableau.column(*nonbasic_col).free {
+                    lower = 0.0;
+                    upper = 0.0;
+                    continue;
+                }
+
+                let ratio = tableau.reduced_cost(*nonbasic_col).max(0.0) / value;
+                if value > 0.0 {
+                    upper = upper.min(ratio);
+                } else {
+                    lower = lower.max(ratio);
+                }
+            }
+            (lower, upper)
+        } else if column.free {
+            (0.0, 0.0)
+        } else {
+            (-tableau.reduced_cost(col).max(0.0), f64::INFINITY)
+        };
 
-    let mut num_artificial = 0;
-    let slack_start_row = problem.num_variables() + 2;
-    for (i, constraint) in problem.constraints().iter().enumerate() {
-        let (value, artificial) = match constraint.kind() {
-            ConstraintKind::LessThanOrEqualTo => (1.0, false),
-            ConstraintKind::GreaterThanOrEqualTo => (-1.0, false),
-            ConstraintKind::EqualTo => (1.0, true),
+        let (decrease, increase) = if mult * column.sign > 0.0 {
+            (-lower, upper)
+        } else {
+            (upper, -lower)
         };
-        matrix.set_value_raw(i + 2, i + slack_start_row, value);
 
-        if artificial {
-            matrix.set_value_raw(0, i + slack_start_row, -1.0);
-            matrix.add_row(Row::new(i + 2), Row::new(0), 1.0);
-            num_artificial += 1;
+        ranging(objective.get(index as u32), decrease, increase, tolerances.optimality)
+    }).collect();
+
+    let constants = problem.constraints().iter().enumerate().map(|(index, constraint)| {
+        // increasing the constant moves each basic variable in proportion to its entry
+        // in the logical column of the constraint
+        let col = Col::new(FIRST_COL + tableau.num_variables + index);
+        let sign = tableau.column(col).sign;
+        let mut lower = f64::NEG_INFINITY;
+        let mut upper = f64::INFINITY;
+        for (i, basic_col) in tableau.basis.iter().enumerate() {
+            let row = Row::new(i + FIRST_ROW);
+            let rate = sign * matrix.value(row, col);
+            let basic = tableau.column(*basic_col);
+            if rate.abs() <= tolerances.pivot || basic.free { continue; }
+
+            let value = matrix.value(row, last_col).max(0.0);
+            let room = (basic.range - value).max(0.0);
+            if rate > 0.0 {
+                lower = lower.max(-value / rate);
+                upper = upper.min(room / rate);
+            } else {
+                upper = upper.min(value / -rate);
+                lower = lower.max(room / rate);
+            }
         }
-    }
-    info!("Set up slack and artifical variables");
-    debug!("{:?}", matrix);
 
-    Ok((matrix, num_artificial))
+        ranging(constraint.constant(), -lower, upper, tolerances.feasibility)
+    }).collect();
+
+    Ok(Sensitivity::new(costs, constants))
+}
+
+fn ranging(value: f64, decrease: f64, increase: f64, tol: f64) -> Ranging {
+    let degenerate = decrease < tol || increase < tol;
+    Ranging::new(value, decrease, increase, degenerate)
 }
 
-pub fn simplex(matrix: &mut Matrix,
-               num_variables: usize,
-               objective_rows: usize,
-               objective_kind: ObjectiveKind) -> Result<Solution> {
+/// Runs the simplex method on the objective in the first row of the tableau's current
+/// view, until the objective is optimal.  If there is no iteration limit in the
+/// options, the iterations in this call are limited based on the size of the tableau.
+/// The `phase` is only used for reporting progress.
+fn simplex(
+    tableau: &mut Tableau,
+    stats: &mut SolveStats,
+    options: &SolverOptions,
+    interrupt: &Interrupt,
+    phase: u32,
+) -> SolveStatus {
     info!("Initializing simplex solver");
 
-    let mut iteration = 0;
+    let size = tableau.matrix.width() * tableau.matrix.height();
+    let max_iterations = options.iteration_limit.unwrap_or(stats.iterations + size as u64);
+    let mut pricing = Pricing::new(options.pricing, tableau);
+
+    // Bland's rule is used while the objective is not improving, and dropped again
+    // once it does
+    let mut degenerate_pivots = 0;
     loop {
-        info!("Iteration {}", iteration);
-        debug!("{:?}", matrix);
-        if iteration >= matrix.width() * matrix.height() {
-            warn!("Warning.  Failed to find solution after {} iterations", iteration);
-            return SolverError::unable_to_solve("Failed to find solution");
+        info!("Iteration {}", stats.iterations);
+        debug!("{:?}", tableau.matrix);
+        if stats.iterations >= max_iterations {
+            warn!("Warning.  Failed to find solution after {} iterations", stats.iterations);
+            return SolveStatus::IterationLimit;
+        }
+
+        if let Some(status) = interrupt.check() {
+            warn!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            return status;
+        }
+
+        let bland = degenerate_pivots >= DEGENERATE_PIVOT_LIMIT;
+        if degenerate_pivots == DEGENERATE_PIVOT_LIMIT {
+            info!("Switching to Bland's rule after {} degenerate pivots", degenerate_pivots);
         }
 
-        let pivot_col = match select_pivot_column(matrix, objective_kind) {
+        let pivot_col = match select_pivot_column(tableau, &mut pricing, bland) {
             None => {
                 info!("No available pivot columns - solution is optimal");
                 break;
@@ -135,104 +958,155 @@ pub fn simplex(matrix: &mut Matrix,
         };
         info!("Selected pivot column {:?}", pivot_col);
 
-        let pivot_row = match select_pivot_row(matrix, pivot_col, objective_rows) {
+        let (step, ratio) = match select_pivot_row(tableau, pivot_col, bland) {
             None => {
                 warn!("Unable to find a pivot row.  function is unbounded.");
-                return SolverError::infeasible("Function is unbounded.");
-            }, Some(row) => row,
+                return SolveStatus::Unbounded;
+            },
+            Some(step) => step,
         };
-        info!("Selected pivot row {:?}", pivot_row);
 
-        simplex_pivot(matrix, pivot_row, pivot_col);
+        if ratio <= tableau.tolerances.pivot {
+            degenerate_pivots += 1;
+        } else {
+            degenerate_pivots = 0;
+        }
+
+        match step {
+            Step::Flip => {
+                info!("Entering column reaches its bound");
+                tableau.flip(pivot_col);
+            },
+            Step::Pivot { row, to_upper } => {
+                info!("Selected pivot row {:?}", row);
+                let leaving = tableau.basis[row.index() - FIRST_ROW];
+                pricing.update(tableau, row, pivot_col);
+                tableau.pivot(row, pivot_col);
+                if to_upper {
+                    tableau.flip(leaving);
+                }
+            }
+        }
         info!("Completed pivot and moving to next iteration.");
 
-        iteration += 1;
+        stats.iterations += 1;
+        let progress = Progress::Simplex {
+            iteration: stats.iterations,
+            objective: tableau.current_objective(),
+            phase,
+        };
+        if let Some(status) = interrupt.report(progress) {
+            info!("Stopping after {} iterations: {:?}", stats.iterations, status);
+            return status;
+        }
     }
 
     info!("Simplex solve complete.");
-
-    produce_solution(matrix, num_variables)
+    SolveStatus::Optimal
 }
 
-fn produce_solution(matrix: &Matrix, num_variables: usize) -> Result<Solution> {
-    let objective = Some(matrix.value(matrix.first_row(), matrix.last_col()));
-    let mut coeffs = vec![0.0; num_variables];
+/// Selects the entering column with the best score under the pricing rule.  Columns
+/// are eligible if their objective row entry is positive, which is a negative reduced
+/// cost.  With Bland's rule, the first eligible column is selected instead.  Free
+/// columns may also enter by decreasing, in which case the column is negated before
+/// being returned.
+fn select_pivot_column(tableau: &mut Tableau, pricing: &mut Pricing, bland: bool) -> Option<Col> {
+    let matrix = &tableau.matrix;
+    let num_cols = matrix.last_col().index() - FIRST_COL;
 
-    let mut out_index = 0;
-    let first_col = matrix.first_col();
-    for col in matrix.cols_range(first_col + 1, first_col + 1 + coeffs.len()) {
-        let mut ident_row = None;
-        let mut skip = false;
-
-        for row in matrix.rows() {
-            if matrix.value(row, col) != 0.0 {
-                if ident_row.is_some() {
-                    skip = true;
-                    break;
-                }
+    // partial pricing scans a segment at a time, starting where the last scan stopped
+    let (start, segment) = match pricing.rule {
+        PricingRule::Partial if !bland => {
+            let segment = ((num_cols as f64).sqrt().ceil() as usize).max(PARTIAL_SEGMENT_MIN);
+            (pricing.next - FIRST_COL, segment)
+        },
+        _ => (0, num_cols),
+    };
 
-                ident_row = Some(row);
-            }
+    let mut best_score = 0.0;
+    let mut best_col = None;
+    for offset in 0..num_cols {
+        let col = Col::new(FIRST_COL + (start + offset) % num_cols);
+        if offset > 0 && offset % segment == 0 && best_col.is_some() {
+            pricing.next = col.index();
+            break;
         }
 
-        if !skip {
-            if let Some(row) = ident_row {
-                coeffs[out_index] = matrix.value(row, matrix.last_col());
-            }
+        let column = tableau.column(col);
+        if column.is_fixed() { continue; }
+
+        let mut value = matrix.value(matrix.first_row(), col);
+        if column.free && !tableau.is_basic(col) {
+            value = value.abs();
         }
+        if value <= tableau.tolerances.optimality { continue; }
 
-        out_index += 1;
+        let score = pricing.score(tableau, col, value);
+        if best_col.is_none() || score > best_score {
+            best_col = Some(col);
+            best_score = score;
+            if bland { break; }
+        }
     }
 
-    let solution = Solution::new(coeffs, objective);
-    info!("Solution found {:?}", solution);
-    Ok(solution)
-}
-
-fn select_pivot_column(matrix: &Matrix, objective_kind: ObjectiveKind) -> Option<Col> {
-    let mut best_val = 0.0;
-    let mut best_col = None;
-
-    for col in matrix.cols_range(matrix.first_col() + 1, matrix.last_col()) {
-        let value = matrix.value(matrix.first_row(), col);
-        match objective_kind {
-            ObjectiveKind::Minimize => {
-                if value > best_val {
-                    best_col = Some(col);
-                    best_val = value;
-                }
-            },
-            ObjectiveKind::Maximize => {
-                if value < best_val {
-                    best_col = Some(col);
-                    best_val = value;
-                }
-            }
+    if let Some(col) = best_col {
+        if tableau.matrix.value(tableau.matrix.first_row(), col) < 0.0 {
+            tableau.negate(col);
         }
     }
 
     best_col
 }
 
-fn select_pivot_row(matrix: &Matrix, pivot_col: Col, num_rows_skip: usize) -> Option<Row> {
-    let mut min = f64::MAX;
-    let mut min_row = None;
+/// Performs the ratio test for increasing the entering column, taking into account
+/// both bounds of each basic variable and the range of the entering column.  Returns
+/// the step along with how far the entering column moves.  With Bland's rule, ties
+/// are broken by the lowest basic column.
+fn select_pivot_row(tableau: &Tableau, pivot_col: Col, bland: bool) -> Option<(Step, f64)> {
+    let matrix = &tableau.matrix;
+    let entering = tableau.column(pivot_col);
+    let tol = tableau.tolerances.pivot;
+
+    let mut min = f64::INFINITY;
+    let mut step = None;
+    let mut leaving = None;
+    if !entering.free && entering.range.is_finite() {
+        min = entering.range;
+        step = Some(Step::Flip);
+    }
 
-    for row in matrix.rows_from(matrix.first_row() + num_rows_skip) {
+    for row in matrix.rows_from(Row::new(FIRST_ROW)) {
         let value = matrix.value(row, pivot_col);
-        if value <= 0.0 { continue; }
+        if value.abs() <= tableau.tolerances.pivot { continue; }
+
+        let basic_col = tableau.basis[row.index() - FIRST_ROW];
+        let basic = tableau.column(basic_col);
+        if basic.free { continue; }
 
-        let min_ratio_test = matrix.value(row, matrix.last_col()) / value;
+        let current = matrix.value(row, matrix.last_col());
+        let (ratio, to_upper) = if value > 0.0 {
+            (current.max(0.0) / value, false)
+        } else if basic.range.is_finite() {
+            ((basic.range - current).max(0.0) / -value, true)
+        } else {
+            continue;
+        };
 
-        if min_ratio_test < 0.0 { continue; }
+        let better = if bland {
+            ratio < min - tol ||
+                (ratio <= min + tol && leaving.map_or(false, |col| basic_col < col))
+        } else {
+            ratio < min
+        };
 
-        if min_ratio_test < min {
-            min_row = Some(row);
-            min = min_ratio_test;
+        if better {
+            min = ratio;
+            step = Some(Step::Pivot { row, to_upper });
+            leaving = Some(basic_col);
         }
     }
 
-    return min_row
+    step.map(|step| (step, min))
 }
 
 fn simplex_pivot(matrix: &mut Matrix, pivot_row: Row, pivot_col: Col) {
@@ -244,7 +1118,8 @@ fn simplex_pivot(matrix: &mut Matrix, pivot_row: Row, pivot_col: Col) {
     for row in matrix.rows() {
         if row == pivot_row { continue; }
 
-        let mult = -1.0 * matrix.value(row, pivot_col);
+        let mult = -matrix.value(row, pivot_col);
+        if mult == 0.0 { continue; }
         matrix.add_row(pivot_row, row, mult);
     }
 }
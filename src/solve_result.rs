@@ -0,0 +1,112 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::time::Duration;
+
+use crate::{Result, Solution, SolverError};
+
+/// The outcome of a solve
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SolveStatus {
+    /// The solution is proven optimal.  For a system of equations, the unique solution
+    /// was found.
+    Optimal,
+
+    /// A feasible solution was found, but the solve stopped before proving it optimal
+    Feasible,
+
+    Infeasible,
+    Unbounded,
+
+    /// The iteration limit was reached before the solve completed or found a feasible
+    /// solution
+    IterationLimit,
+
+    /// The time limit was reached before the solve completed or found a feasible solution
+    TimeLimit,
+
+    /// The solve was stopped before it completed or found a feasible solution
+    Interrupted,
+}
+
+/// Statistics gathered over the course of a solve
+#[derive(Debug, Clone, Default)]
+pub struct SolveStats {
+    pub(crate) iterations: u64,
+    pub(crate) nodes: u64,
+    pub(crate) elapsed: Duration,
+}
+
+impl SolveStats {
+    /// The number of simplex iterations performed
+    pub fn iterations(&self) -> u64 { self.iterations }
+
+    /// The number of integer assignments checked by the brute force search
+    pub fn nodes(&self) -> u64 { self.nodes }
+
+    /// The wall clock time taken by the solve
+    pub fn elapsed(&self) -> Duration { self.elapsed }
+
+    pub(crate) fn add(&mut self, other: &SolveStats) {
+        self.iterations += other.iterations;
+        self.nodes += other.nodes;
+    }
+}
+
+/// The status of a solve, along with the best solution found, if any
+#[derive(Debug)]
+pub struct SolveResult {
+    status: SolveStatus,
+    solution: Option<Solution>,
+    stats: SolveStats,
+}
+
+impl SolveResult {
+    pub fn new(status: SolveStatus, solution: Option<Solution>, stats: SolveStats) -> SolveResult {
+        SolveResult {
+            status,
+            solution,
+            stats,
+        }
+    }
+
+    pub fn status(&self) -> SolveStatus { self.status }
+
+    /// The best solution found.  This is only guaranteed to be optimal when the
+    /// status is `Optimal`, and is the best feasible solution so far when it is
+    /// `Feasible`.
+    pub fn solution(&self) -> Option<&Solution> { self.solution.as_ref() }
+
+    pub fn stats(&self) -> &SolveStats { &self.stats }
+
+    pub(crate) fn stats_mut(&mut self) -> &mut SolveStats { &mut self.stats }
+
+    pub(crate) fn solution_mut(&mut self) -> Option<&mut Solution> { self.solution.as_mut() }
+
+    /// Returns the solution if it is optimal, otherwise an error describing the status
+    pub fn into_solution(self) -> Result<Solution> {
+        match (self.status, self.solution) {
+            (SolveStatus::Optimal, Some(solution)) => Ok(solution),
+            (SolveStatus::Infeasible, _) =>
+                SolverError::infeasible("No feasible solution exists."),
+            (SolveStatus::Unbounded, _) => SolverError::unbounded("Function is unbounded."),
+            (SolveStatus::IterationLimit, _) => SolverError::unable_to_solve(
+                format!("Failed to find solution after {} iterations.", self.stats.iterations)),
+            (status, _) => SolverError::unable_to_solve(
+                format!("Solve finished without an optimal solution: {:?}", status)),
+        }
+    }
+}
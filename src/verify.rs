@@ -0,0 +1,108 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use crate::{Result, SolverError, Problem, Solution, Variable, VariableKind, ConstraintRef,
+    Tolerances};
+
+/// The result of checking a solution against every constraint, variable bound and
+/// integrality requirement of a problem.  Only violations larger than the tolerances
+/// used are reported.
+#[derive(Debug, Clone)]
+pub struct VerifyReport {
+    max_violation: f64,
+    constraint_violations: Vec<(ConstraintRef, f64)>,
+    bound_violations: Vec<(Variable, f64)>,
+    integrality_violations: Vec<(Variable, f64)>,
+    objective: Option<f64>,
+}
+
+impl VerifyReport {
+    /// Whether the solution satisfies all constraints, bounds and integrality
+    /// requirements
+    pub fn is_feasible(&self) -> bool {
+        self.constraint_violations.is_empty() && self.bound_violations.is_empty() &&
+            self.integrality_violations.is_empty()
+    }
+
+    /// The largest violation of any constraint or variable bound, or zero if none are
+    /// violated at all
+    pub fn max_violation(&self) -> f64 { self.max_violation }
+
+    /// Each violated constraint, along with the amount it is violated by
+    pub fn constraint_violations(&self) -> &[(ConstraintRef, f64)] { &self.constraint_violations }
+
+    /// Each variable outside its bounds, along with the distance to the nearest bound
+    pub fn bound_violations(&self) -> &[(Variable, f64)] { &self.bound_violations }
+
+    /// Each integer variable with a fractional value, along with the distance to the
+    /// nearest integer
+    pub fn integrality_violations(&self) -> &[(Variable, f64)] { &self.integrality_violations }
+
+    /// The objective value recomputed from the solution's variable values
+    pub fn objective(&self) -> Option<f64> { self.objective }
+}
+
+pub(crate) fn verify(
+    problem: &Problem,
+    solution: &Solution,
+    tolerances: &Tolerances
+) -> Result<VerifyReport> {
+    let num_values = solution.iter().count();
+    if num_values != problem.num_variables() {
+        return SolverError::invalid_solution(format!("Solution has {} values, but the problem \
+            has {} variables.", num_values, problem.num_variables()));
+    }
+
+    let mut max_violation: f64 = 0.0;
+
+    let mut constraint_violations = Vec::new();
+    for (index, constraint) in problem.constraints().iter().enumerate() {
+        let violation = -constraint.slack(constraint.expr().evaluate(solution));
+        max_violation = max_violation.max(violation);
+        if violation > tolerances.feasibility {
+            constraint_violations.push((ConstraintRef::from(index), violation));
+        }
+    }
+
+    let mut bound_violations = Vec::new();
+    let mut integrality_violations = Vec::new();
+    for variable in problem.variables() {
+        let value = solution.value_of(*variable);
+        let (lower, upper) = problem.bounds(*variable);
+        let violation = (lower - value).max(value - upper);
+        max_violation = max_violation.max(violation);
+        if violation > tolerances.feasibility {
+            bound_violations.push((*variable, violation));
+        }
+
+        if let VariableKind::Integer(..) = variable.kind() {
+            let distance = (value - value.round()).abs();
+            if distance > tolerances.integrality {
+                integrality_violations.push((*variable, distance));
+            }
+        }
+    }
+
+    let objective = problem.objective_expr().map(|expr| expr.evaluate(solution));
+
+    Ok(VerifyReport {
+        max_violation,
+        constraint_violations,
+        bound_violations,
+        integrality_violations,
+        objective,
+    })
+}
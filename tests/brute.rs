@@ -15,7 +15,7 @@
 //  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
 
 use rusolve::{Constraint, ConstraintKind, Problem, Expression, ObjectiveKind, Result, ErrorKind,
-    create_constraints, create_expr, create_constraint};
+    Variable, VariableKind, create_constraints, create_expr, create_constraint};
 
 mod common;
 use crate::common::{setup, solution_eq, solution_err};
@@ -87,3 +87,63 @@ fn brute_bigger() -> Result<()> {
     );
     solution_eq(problem, vec![0.0, 1.0, 0.0, 1.0, 0.0], Some(9.0))
 }
+
+#[test]
+fn brute_sparse() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(4);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[3]).le(1.0))?;
+    problem.add_constraint((x[1] + x[2]).le(1.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1] + 3.0 * x[2] + 4.0 * x[3], ObjectiveKind::Maximize);
+    solution_eq(problem, vec![0.0, 0.0, 1.0, 1.0], Some(7.0))
+}
+
+#[test]
+fn brute_range() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1] + x[2]).range(1.0, 2.0))?;
+    problem.set_objective(3.0 * x[0] + 2.0 * x[1] + x[2], ObjectiveKind::Minimize);
+    solution_eq(problem, vec![0.0, 0.0, 1.0], Some(1.0))
+}
+
+#[test]
+fn brute_mixed() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Integer(0, 3));
+    let y = problem.add_variable(VariableKind::Continuous);
+    problem.set_bounds(y, 0.0, 1.2)?;
+    problem.add_constraint((x + 2.0 * y).le(4.5))?;
+    problem.set_objective(x + y, ObjectiveKind::Maximize);
+    solution_eq(problem, vec![3.0, 0.75], Some(3.75))
+}
+
+#[test]
+fn brute_mixed_infeasible() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Integer(0, 3));
+    let y = problem.add_variable(VariableKind::Continuous);
+    problem.add_constraint((x + y).le(-1.0))?;
+    problem.set_objective(x + y, ObjectiveKind::Maximize);
+    solution_err(problem, ErrorKind::Infeasible)
+}
+
+#[test]
+fn brute_mixed_unbounded() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Integer(0, 3));
+    let y = problem.add_variable(VariableKind::Continuous);
+    problem.add_constraint((x - y).le(1.0))?;
+    problem.set_objective(x + y, ObjectiveKind::Maximize);
+    solution_err(problem, ErrorKind::Unbounded)
+}
@@ -14,11 +14,12 @@
 //  You should have received a copy of the GNU General Public License
 //  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
 
-use std::sync::{Once};
+#![allow(dead_code)]
 
-use env_logger;
+use std::sync::{Once};
 
-use rusolve::{SolverError, ErrorKind, Result, Problem};
+use rusolve::{SolverError, ErrorKind, Result, Problem, Tolerances, SolverOptions, SolveResult,
+    SolveStatus, Algorithm};
 
 static INIT: Once = Once::new();
 
@@ -32,6 +33,37 @@ pub fn setup() -> Result<()> {
 
 const TOLERANCE: f64 = 1e-6;
 
+pub fn values_eq(expected: &[f64], found: &[f64], tol: f64) -> bool {
+    expected.len() == found.len() &&
+        expected.iter().zip(found).all(|(a, b)| (a - b).abs() <= tol)
+}
+
+/// Solves the problem with both the tableau simplex method and `options`, checking that
+/// they produce the same solution, duals and reduced costs to within `tol`.  The problem
+/// must have a unique primal and dual optimum.
+pub fn solve_both(problem: &Problem, options: &SolverOptions, tol: f64) -> Result<SolveResult> {
+    let simplex = SolverOptions { algorithm: Some(Algorithm::Simplex), ..SolverOptions::default() };
+    let expected = problem.solve_with(&simplex)?;
+    let result = problem.solve_detailed_with(options)?;
+    let found = match result.solution() {
+        Some(solution) if result.status() == SolveStatus::Optimal => solution,
+        _ => return SolverError::invalid_solution(format!("Expected Optimal, got {:?}", result)),
+    };
+
+    let expected_values: Vec<f64> = expected.iter().cloned().collect();
+    let found_values: Vec<f64> = found.iter().cloned().collect();
+    let matches = values_eq(&expected_values, &found_values, tol) &&
+        values_eq(expected.duals().unwrap(), found.duals().unwrap(), tol) &&
+        values_eq(expected.reduced_costs().unwrap(), found.reduced_costs().unwrap(), tol) &&
+        values_eq(&[expected.objective().unwrap()], &[found.objective().unwrap()], tol);
+
+    if !matches {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, found));
+    }
+
+    Ok(result)
+}
+
 pub fn solution_err(problem: Problem, kind: ErrorKind) -> Result<()> {
     match problem.solve() {
         Err(error) => {
@@ -45,7 +77,28 @@ pub fn solution_err(problem: Problem, kind: ErrorKind) -> Result<()> {
 }
 
 pub fn solution_eq(problem: Problem, vars: Vec<f64>, objective: Option<f64>) -> Result<()> {
-    let solution = problem.solve()?;
+    solution_eq_with(problem, &SolverOptions::default(), vars, objective)
+}
+
+pub fn solution_eq_with(
+    problem: Problem,
+    options: &SolverOptions,
+    vars: Vec<f64>,
+    objective: Option<f64>,
+) -> Result<()> {
+    let solution = problem.solve_with(options)?;
+
+    let report = problem.verify(&solution, &Tolerances::default())?;
+    if !report.is_feasible() {
+        return SolverError::invalid_solution(format!("Solution is infeasible: {:?}", report));
+    }
+
+    match (report.objective(), solution.objective()) {
+        (Some(computed), Some(found)) if (computed - found).abs() > TOLERANCE =>
+            return SolverError::invalid_solution(format!("Objective {} was reported as {}",
+                                                         computed, found)),
+        _ => (),
+    }
 
     for index in 0..vars.len() {
         if (solution.value(index) - vars[index]).abs() > TOLERANCE {
@@ -73,3 +126,19 @@ pub fn solution_eq(problem: Problem, vars: Vec<f64>, objective: Option<f64>) ->
         }
     }
 }
+
+pub fn duals_eq(problem: Problem, duals: Vec<f64>, reduced_costs: Vec<f64>) -> Result<()> {
+    let solution = problem.solve()?;
+
+    let found = match (solution.duals(), solution.reduced_costs()) {
+        (Some(found_duals), Some(found_costs)) => (found_duals, found_costs),
+        _ => return SolverError::invalid_solution("Expected duals and reduced costs"),
+    };
+
+    if !values_eq(&duals, found.0, TOLERANCE) || !values_eq(&reduced_costs, found.1, TOLERANCE) {
+        return SolverError::invalid_solution(format!("Expected duals {:?} and reduced costs {:?}, \
+            got {:?} and {:?}", duals, reduced_costs, found.0, found.1));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,146 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use rusolve::{Problem, ObjectiveKind, Result, ErrorKind, Variable, SolverError, SolverOptions,
+    SolveStatus, Algorithm, Solution, Expression};
+
+mod common;
+use crate::common::{setup, solution_eq_with};
+
+fn dual_options(solution: Option<&Solution>) -> SolverOptions {
+    SolverOptions {
+        algorithm: Some(Algorithm::DualSimplex),
+        basis: solution.and_then(|solution| solution.basis().cloned()),
+        ..SolverOptions::default()
+    }
+}
+
+/// A diet problem, minimizing cost subject to minimum amounts of each nutrient
+fn diet_problem() -> Result<Problem> {
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((2.0 * x[0] + x[1] + x[2]).ge(8.0))?;
+    problem.add_constraint((x[0] + 3.0 * x[1] + 2.0 * x[2]).ge(12.0))?;
+    problem.add_constraint((x[0] + x[1] + 4.0 * x[2]).ge(10.0))?;
+    problem.set_objective(3.0 * x[0] + 4.0 * x[1] + 5.0 * x[2], ObjectiveKind::Minimize);
+    Ok(problem)
+}
+
+/// Solves the problem starting from the basis of `solution`, and checks that this
+/// matches solving from scratch
+fn resolve(problem: &Problem, solution: &Solution) -> Result<u64> {
+    let expected = problem.solve()?;
+    let result = problem.solve_detailed_with(&dual_options(Some(solution)))?;
+    let iterations = result.stats().iterations();
+    let found = result.into_solution()?;
+    if (expected.objective().unwrap() - found.objective().unwrap()).abs() > 1e-6 {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, found));
+    }
+
+    Ok(iterations)
+}
+
+#[test]
+fn dual_simplex_diet() -> Result<()> {
+    setup()?;
+
+    let problem = diet_problem()?;
+    solution_eq_with(problem, &dual_options(None), vec![2.125, 2.375, 1.375], Some(22.75))
+}
+
+#[test]
+fn dual_simplex_not_dual_feasible() -> Result<()> {
+    setup()?;
+
+    // the initial basis is not dual feasible, so the primal method is used instead
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1]).le(10.0))?;
+    problem.add_constraint((x[0] + 2.0 * x[1]).le(15.0))?;
+    problem.set_objective(2.0 * x[0] + 3.0 * x[1], ObjectiveKind::Maximize);
+    solution_eq_with(problem, &dual_options(None), vec![5.0, 5.0], Some(25.0))
+}
+
+#[test]
+fn dual_simplex_warm_start_constant() -> Result<()> {
+    setup()?;
+
+    let mut problem = diet_problem()?;
+    let solution = problem.solve()?;
+
+    problem.set_constant(1, 20.0)?;
+    let iterations = resolve(&problem, &solution)?;
+    if iterations > 2 {
+        return SolverError::invalid_solution(format!("Expected at most 2 iterations, got {}",
+                                                     iterations));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn dual_simplex_warm_start_cut() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((2.0 * x[0] + 2.0 * x[1]).le(9.0))?;
+    problem.add_constraint((4.0 * x[0] - 2.0 * x[1]).le(5.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1], ObjectiveKind::Maximize);
+    let solution = problem.solve()?;
+
+    // a cut removing the current solution, whose logical column starts basic
+    problem.add_constraint(Expression::from(x[1]).le(4.0))?;
+    let iterations = resolve(&problem, &solution)?;
+    if iterations != 1 {
+        return SolverError::invalid_solution(format!("Expected 1 iteration, got {}", iterations));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn dual_simplex_warm_start_infeasible() -> Result<()> {
+    setup()?;
+
+    let mut problem = diet_problem()?;
+    let solution = problem.solve()?;
+
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1] + x[2]).le(3.0))?;
+    let status = problem.solve_detailed_with(&dual_options(Some(&solution)))?.status();
+    if status != SolveStatus::Infeasible {
+        return SolverError::invalid_solution(format!("Expected Infeasible, got {:?}", status));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn dual_simplex_basis_mismatch() -> Result<()> {
+    setup()?;
+
+    let mut problem = diet_problem()?;
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1] + x[2]).le(10.0))?;
+    let solution = problem.solve()?;
+
+    // a basis with more constraints than the problem can't be used
+    match diet_problem()?.solve_detailed_with(&dual_options(Some(&solution))) {
+        Err(e) if e.kind() == ErrorKind::InvalidSolution => Ok(()),
+        _ => SolverError::invalid_solution("Expected an InvalidSolution error"),
+    }
+}
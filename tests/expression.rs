@@ -0,0 +1,107 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2019 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use rusolve::{ConstraintKind, Problem, Expression, ObjectiveKind, Result, Variable, SolverError};
+
+mod common;
+use crate::common::{setup, solution_eq};
+
+fn vars(problem: &Problem) -> Vec<Variable> {
+    problem.variables().cloned().collect()
+}
+
+#[test]
+fn expression_ops() -> Result<()> {
+    setup()?;
+
+    let problem = Problem::continuous(3);
+    let v = vars(&problem);
+    let (x, y, z) = (v[0], v[1], v[2]);
+
+    let expr = 2.0 * x + 3.0 * y - z;
+    let expected = [2.0, 3.0, -1.0];
+    for (index, value) in expected.iter().enumerate() {
+        if expr.get(index as u32) != *value {
+            return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, expr));
+        }
+    }
+
+    let expr = -(x - 2.0 * y) * 2.0;
+    if expr.get(0) != -2.0 || expr.get(1) != 4.0 || expr.get(2) != 0.0 {
+        return SolverError::invalid_solution(format!("Unexpected expression {:?}", expr));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn expression_terms_accumulate() -> Result<()> {
+    setup()?;
+
+    let problem = Problem::continuous(2);
+    let v = vars(&problem);
+
+    let mut expr = Expression::default();
+    expr.add_term(2.0, v[0]);
+    expr.add_term(3.0, v[0]);
+    expr += v[1];
+    expr -= 4.0 * v[1];
+    let expr = expr + v[0];
+
+    if expr.get(0) != 6.0 || expr.get(1) != -3.0 {
+        return SolverError::invalid_solution(format!("Unexpected expression {:?}", expr));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn expression_constraint_builders() -> Result<()> {
+    setup()?;
+
+    let problem = Problem::continuous(2);
+    let v = vars(&problem);
+
+    let le = (v[0] + v[1]).le(5.0);
+    let ge = (v[0] - v[1]).ge(1.0);
+    let eq = Expression::from(v[1]).eq(3.0);
+
+    match (le.kind(), ge.kind(), eq.kind()) {
+        (ConstraintKind::LessThanOrEqualTo, ConstraintKind::GreaterThanOrEqualTo,
+         ConstraintKind::EqualTo) => (),
+        kinds => return SolverError::invalid_solution(format!("Unexpected kinds {:?}", kinds)),
+    }
+
+    if le.constant() != 5.0 || ge.constant() != 1.0 || eq.constant() != 3.0 {
+        return SolverError::invalid_solution("Unexpected constraint constants");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn expression_simplex() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let v = vars(&problem);
+    let (x, y) = (v[0], v[1]);
+
+    problem.add_constraint((x + y).le(10.0))?;
+    problem.add_constraint((x + 2.0 * y).le(15.0))?;
+    problem.set_objective(2.0 * x + 3.0 * y, ObjectiveKind::Maximize);
+    solution_eq(problem, vec![5.0, 5.0], Some(25.0))
+}
@@ -14,7 +14,7 @@
 //  You should have received a copy of the GNU General Public License
 //  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
 
-use rusolve::{Constraint, ConstraintKind, Problem, Expression, Result,
+use rusolve::{Constraint, ConstraintKind, Problem, Expression, Result, Variable, SolverError,
     create_constraints, create_constraint, ErrorKind};
 
 mod common;
@@ -80,3 +80,29 @@ fn gaussian_elim_reduced() -> Result<()> {
     )?;
     solution_eq(problem, vec![0.5, 1.0, 1.0], None)
 }
+
+#[test]
+fn gaussian_elim_sparse() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint(Expression::from(x[0]).eq(1.0))?;
+    problem.add_constraint((x[1] + x[2]).eq(3.0))?;
+    problem.add_constraint(Expression::from(x[2]).eq(2.0))?;
+    solution_eq(problem, vec![1.0, 1.0, 2.0], None)
+}
+
+#[test]
+fn gaussian_inequality_named() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1]).eq(1.0))?;
+    problem.add_constraint((x[0] - x[1]).le(2.0).named("upper"))?;
+    match problem.solve() {
+        Err(e) if e.kind() == ErrorKind::InvalidConstraint && e.message().contains("'upper'") => Ok(()),
+        _ => SolverError::invalid_solution("Expected error to name the constraint"),
+    }
+}
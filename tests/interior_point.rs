@@ -0,0 +1,425 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::f64;
+use std::sync::{Arc, Mutex};
+
+use rusolve::{Constraint, ConstraintKind, Problem, Expression, ObjectiveKind, Result, Variable,
+    VariableKind, SolverError, SolverOptions, SolveStatus, Tolerances, Algorithm, Progress,
+    ProgressAction, ProgressCallback, create_constraints, create_expr, create_constraint};
+
+mod common;
+use crate::common::{setup, values_eq, solve_both};
+
+/// Interior point solutions are only as accurate as the duality gap allows
+const TOLERANCE: f64 = 1e-5;
+
+fn interior_options() -> SolverOptions {
+    SolverOptions { algorithm: Some(Algorithm::InteriorPoint), ..SolverOptions::default() }
+}
+
+fn status_eq(problem: &Problem, options: &SolverOptions, expected: SolveStatus) -> Result<()> {
+    let status = problem.solve_detailed_with(options)?.status();
+    if status != expected {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, status));
+    }
+
+    Ok(())
+}
+
+/// The problems of the simplex tests that have a unique primal and dual optimum
+fn simplex_problems() -> Result<Vec<Problem>> {
+    let mut problems = Vec::new();
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, -1.0 ;ge; -5.0],
+            [1.0,  0.0 ;le; 10.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [3.0, 2.0, 1.0 ;eq; 10.0 ],
+            [2.0, 5.0, 3.0 ;eq; 15.0 ])
+    )?;
+    problem.set_objective(create_expr!(-2.0, -3.0, -4.0), ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;le; 10.0],
+            [1.0, 2.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [ 3.0, 2.0, 1.0 ;le; 10.0],
+            [ 2.0, 5.0, 3.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(-2.0, -3.0, -4.0), ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(6);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1]).le(4.0))?;
+    problem.add_constraint((x[2] + x[3]).le(6.0))?;
+    problem.add_constraint((x[4] + x[5]).le(2.0))?;
+    problem.set_objective(
+        x[0] + 2.0 * x[1] + 3.0 * x[2] + 4.0 * x[3] + 5.0 * x[4] + 6.0 * x[5],
+        ObjectiveKind::Maximize
+    );
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;ge; 2.0],
+            [1.0, 0.0 ;ge; 0.5])
+    )?;
+    problem.set_objective(create_expr!(2.0, 1.0), ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -5.0, 10.0)?;
+    problem.set_bounds(x[1], f64::NEG_INFINITY, f64::INFINITY)?;
+    problem.add_constraint((x[0] + x[1]).ge(1.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1], ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -5.0, f64::INFINITY)?;
+    problem.set_bounds(x[1], f64::NEG_INFINITY, 4.0)?;
+    problem.set_bounds(x[2], 3.0, 3.0)?;
+    problem.add_constraint(Expression::from(x[0]).le(-2.0))?;
+    problem.add_constraint((x[1] + x[2]).le(10.0))?;
+    problem.set_objective(x[0] + x[1] + x[2], ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -5.0, 10.0)?;
+    problem.set_bounds(x[1], 2.0, f64::INFINITY)?;
+    problem.set_objective(x[0] + x[1], ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    for var in x.iter() {
+        problem.set_bounds(*var, 0.0, 1.0)?;
+    }
+    problem.add_constraint((10.0 * x[0] + 20.0 * x[1] + 30.0 * x[2]).le(50.0))?;
+    problem.set_objective(60.0 * x[0] + 100.0 * x[1] + 120.0 * x[2], ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    for var in x.iter() {
+        problem.set_bounds(*var, 0.0, 2.0)?;
+    }
+    problem.add_constraint((x[0] + x[1] + x[2]).ge(5.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1] + 3.0 * x[2], ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -3.0, -1.0)?;
+    problem.set_bounds(x[1], f64::NEG_INFINITY, 2.0)?;
+    problem.add_constraint((x[0] + x[1]).le(0.0))?;
+    problem.set_objective(2.0 * x[0] + x[1], ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] - x[1]).range(2.0, 4.0))?;
+    problem.add_constraint((x[0] + x[1]).le(10.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1], ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] - x[1]).range(2.0, 4.0))?;
+    problem.add_constraint(Expression::from(x[0]).ge(5.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1], ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [0.0, 2.0 ;le; 12.0],
+            [3.0, 2.0 ;le; 18.0])
+    )?;
+    problem.set_objective(create_expr!(3.0, 5.0), ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [0.0, 2.0 ;le; 12.0],
+            [3.0, 2.0 ;le; 18.0])
+    )?;
+    problem.set_objective(create_expr!(-3.0, -5.0), ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;ge; 4.0],
+            [1.0, 3.0 ;ge; 6.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Continuous);
+    let y = problem.add_variable(VariableKind::Continuous);
+    let z = problem.add_variable(VariableKind::Continuous);
+    problem.set_bounds(x, 0.0, 2.0)?;
+    problem.add_constraint((x + y + z).le(6.0))?;
+    problem.add_constraint((x - y).eq(-1.0))?;
+    problem.set_objective(3.0 * x + y - z, ObjectiveKind::Maximize);
+    problems.push(problem);
+
+    let mut problem = Problem::continuous(4);
+    problem.add_constraints(create_constraints!(
+            [0.25, -60.0, -0.04, 9.0 ;le; 0.0],
+            [0.5,  -90.0, -0.02, 3.0 ;le; 0.0],
+            [0.0,    0.0,  1.0,  0.0 ;le; 1.0])
+    )?;
+    problem.set_objective(create_expr!(-0.75, 150.0, -0.02, 6.0), ObjectiveKind::Minimize);
+    problems.push(problem);
+
+    Ok(problems)
+}
+
+#[test]
+fn interior_point_matches_simplex() -> Result<()> {
+    setup()?;
+
+    for problem in simplex_problems()? {
+        solve_both(&problem, &interior_options(), TOLERANCE)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn interior_point_infeasible_unbounded() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;le; 2.0],
+            [1.0, 1.0 ;ge; 3.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 1.0), ObjectiveKind::Maximize);
+    status_eq(&problem, &interior_options(), SolveStatus::Infeasible)?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, -1.0 ;le; -5.0],
+            [1.0,  0.0 ;le; 10.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+    status_eq(&problem, &interior_options(), SolveStatus::Unbounded)?;
+
+    // both the problem and its dual are infeasible
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint(Expression::from(x[0]).le(-1.0))?;
+    problem.set_objective(x[0] + x[1], ObjectiveKind::Maximize);
+    status_eq(&problem, &interior_options(), SolveStatus::Infeasible)
+}
+
+#[test]
+fn interior_point_iteration_limit() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [ 3.0, 2.0, 1.0 ;le; 10.0],
+            [ 2.0, 5.0, 3.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(-2.0, -3.0, -4.0), ObjectiveKind::Minimize);
+
+    let options = SolverOptions { iteration_limit: Some(2), ..interior_options() };
+    status_eq(&problem, &options, SolveStatus::IterationLimit)
+}
+
+#[test]
+fn interior_point_progress() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;le; 10.0],
+            [1.0, 2.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+
+    let gaps = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&gaps);
+    let progress = ProgressCallback::new(move |progress| {
+        if let Progress::InteriorPoint { gap, .. } = progress {
+            recorded.lock().unwrap().push(*gap);
+        }
+        ProgressAction::Continue
+    });
+    let options = SolverOptions {
+        progress: Some(progress),
+        skip_crossover: true,
+        ..interior_options()
+    };
+    let result = problem.solve_detailed_with(&options)?;
+
+    let gaps = gaps.lock().unwrap();
+    if gaps.len() as u64 != result.stats().iterations() || gaps.is_empty() {
+        return SolverError::invalid_solution(format!("Expected a report per iteration, got {:?} \
+            for {:?}", gaps, result));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn interior_point_dense() -> Result<()> {
+    setup()?;
+
+    let size = 60;
+    let mut problem = Problem::continuous(size + size / 2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    for i in 0..size as usize {
+        let mut expr = Expression::default();
+        for (j, var) in x.iter().enumerate() {
+            let coeff = ((i * 7 + j * 13) % 11) as f64;
+            if coeff > 3.0 {
+                expr.add_term(coeff, *var);
+            }
+        }
+        problem.add_constraint(expr.le(10.0 + (i % 7) as f64))?;
+    }
+
+    let mut objective = Expression::default();
+    for (i, var) in x.iter().enumerate() {
+        objective.add_term(1.0 + (i % 5) as f64, *var);
+    }
+    problem.set_objective(objective, ObjectiveKind::Maximize);
+
+    let expected = problem.solve()?.objective().unwrap();
+    let result = problem.solve_detailed_with(&interior_options())?;
+    let iterations = result.stats().iterations();
+    let objective = result.into_solution()?.objective().unwrap();
+    if !values_eq(&[expected], &[objective], TOLERANCE) || iterations > 50 {
+        return SolverError::invalid_solution(format!("Expected objective {}, got {} after {} \
+            iterations", expected, objective, iterations));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn interior_point_crossover_basis() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [0.0, 2.0 ;le; 12.0],
+            [3.0, 2.0 ;le; 18.0])
+    )?;
+    problem.set_objective(create_expr!(3.0, 5.0), ObjectiveKind::Maximize);
+
+    let expected = problem.solve()?;
+    let found = problem.solve_with(&interior_options())?;
+    if expected.basis() != found.basis() {
+        return SolverError::invalid_solution(format!("Expected basis {:?}, got {:?}",
+                                                     expected.basis(), found.basis()));
+    }
+
+    let expected = problem.sensitivity(&expected, &Tolerances::default())?;
+    let found = problem.sensitivity(&found, &interior_options().tolerances)?;
+    if expected.costs() != found.costs() || expected.constants() != found.constants() {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, found));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn interior_point_crossover_vertex() -> Result<()> {
+    setup()?;
+
+    // every point on the edge from (2, 8) to (8, 2) is optimal, and the interior point
+    // method converges to the middle of it
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], 0.0, 8.0)?;
+    problem.set_bounds(x[1], 0.0, 8.0)?;
+    problem.add_constraint((x[0] + x[1]).le(10.0))?;
+    problem.set_objective(x[0] + x[1], ObjectiveKind::Maximize);
+
+    let options = SolverOptions { skip_crossover: true, ..interior_options() };
+    let interior = problem.solve_with(&options)?;
+    if !values_eq(&[5.0, 5.0], &[interior.value(0), interior.value(1)], TOLERANCE) ||
+        interior.basis().is_some() {
+        return SolverError::invalid_solution(format!("Expected the centre of the edge, got {:?}",
+                                                     interior));
+    }
+
+    let solution = problem.solve_with(&interior_options())?;
+    let values = [solution.value(0), solution.value(1)];
+    let vertex = values_eq(&[2.0, 8.0], &values, TOLERANCE) ||
+        values_eq(&[8.0, 2.0], &values, TOLERANCE);
+    if !vertex || !values_eq(&[10.0], &[solution.objective().unwrap()], TOLERANCE) {
+        return SolverError::invalid_solution(format!("Expected a vertex, got {:?}", solution));
+    }
+
+    problem.sensitivity(&solution, &interior_options().tolerances)?;
+    Ok(())
+}
+
+#[test]
+fn interior_point_crossover_warm_start() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((2.0 * x[0] + x[1] + x[2]).ge(8.0))?;
+    problem.add_constraint((x[0] + 3.0 * x[1] + x[2]).ge(12.0))?;
+    problem.add_constraint((x[0] + x[1] + 4.0 * x[2]).ge(10.0))?;
+    problem.set_objective(4.0 * x[0] + 3.0 * x[1] + 5.0 * x[2], ObjectiveKind::Minimize);
+
+    let solution = problem.solve_with(&interior_options())?;
+    problem.set_constant(1, 20.0)?;
+    let options = SolverOptions {
+        algorithm: Some(Algorithm::DualSimplex),
+        basis: solution.basis().cloned(),
+        ..SolverOptions::default()
+    };
+    let result = problem.solve_detailed_with(&options)?;
+    let expected = problem.solve()?.objective().unwrap();
+    let iterations = result.stats().iterations();
+    let objective = result.into_solution()?.objective().unwrap();
+    if !values_eq(&[expected], &[objective], TOLERANCE) || iterations > 2 {
+        return SolverError::invalid_solution(format!("Expected objective {} in at most 2 \
+            iterations, got {} in {}", expected, objective, iterations));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use rusolve::{LuDecomposition, Matrix, Row, Col, Result, SolverError, ErrorKind};
+
+mod common;
+use crate::common::{setup, values_eq};
+
+const TOLERANCE: f64 = 1e-9;
+const PIVOT: f64 = 1e-12;
+
+fn matrix_values(matrix: &Matrix) -> Vec<f64> {
+    matrix.rows().flat_map(|row| matrix.cols().map(move |col| matrix.value(row, col))).collect()
+}
+
+/// A matrix that needs row swaps, since its first pivot is zero
+fn pivoting_matrix() -> Result<Matrix> {
+    Matrix::new(3, 3, vec![0.0, 2.0, 1.0,
+                           1.0, 1.0, 1.0,
+                           4.0, -1.0, 3.0])
+}
+
+#[test]
+fn lu_solve() -> Result<()> {
+    setup()?;
+
+    let lu = LuDecomposition::new(&pivoting_matrix()?, PIVOT)?;
+    for expected in [[1.0, 2.0, 3.0], [-1.0, 0.5, 0.0], [0.0, 0.0, 0.0]].iter() {
+        let b = pivoting_matrix()?.mul_vec(expected)?;
+        let x = lu.solve(&b)?;
+        if !values_eq(expected, &x, TOLERANCE) {
+            return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, x));
+        }
+    }
+
+    // PA = LU
+    let mut permuted = Matrix::zeros(3, 3);
+    let matrix = pivoting_matrix()?;
+    for (row, original) in lu.permutation().iter().enumerate() {
+        for col in matrix.cols() {
+            permuted.set_value(Row::new(row), col, matrix.value(Row::new(*original), col));
+        }
+    }
+    let product = lu.lower().matmul(&lu.upper())?;
+    if !values_eq(&matrix_values(&permuted), &matrix_values(&product), TOLERANCE) {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", permuted, product));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lu_solve_matrix() -> Result<()> {
+    setup()?;
+
+    let matrix = pivoting_matrix()?;
+    let lu = LuDecomposition::new(&matrix, PIVOT)?;
+    let expected = Matrix::new(2, 3, vec![1.0, -1.0,
+                                          2.0, 0.5,
+                                          3.0, 0.0])?;
+    let x = lu.solve_matrix(&matrix.matmul(&expected)?)?;
+    if !values_eq(&matrix_values(&expected), &matrix_values(&x), TOLERANCE) {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, x));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lu_determinant_inverse() -> Result<()> {
+    setup()?;
+
+    let matrix = pivoting_matrix()?;
+    let lu = LuDecomposition::new(&matrix, PIVOT)?;
+    // expanding along the first row: -2 * (3 - 4) + 1 * (-1 - 4)
+    if (lu.determinant() + 3.0).abs() > TOLERANCE {
+        return SolverError::invalid_solution(format!("Expected -3, got {}", lu.determinant()));
+    }
+
+    let product = matrix.matmul(&lu.inverse()?)?;
+    if !values_eq(&matrix_values(&Matrix::identity(3)), &matrix_values(&product), TOLERANCE) {
+        return SolverError::invalid_solution(format!("Expected the identity, got {:?}", product));
+    }
+
+    let lu = LuDecomposition::new(&Matrix::identity(4).scale(2.0), PIVOT)?;
+    if (lu.determinant() - 16.0).abs() > TOLERANCE {
+        return SolverError::invalid_solution(format!("Expected 16, got {}", lu.determinant()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lu_errors() -> Result<()> {
+    setup()?;
+
+    let singular = Matrix::new(3, 3, vec![1.0, 2.0, 3.0,
+                                          2.0, 4.0, 6.0,
+                                          1.0, 0.0, 1.0])?;
+    let lu = LuDecomposition::new(&singular, PIVOT)?;
+    if !lu.is_singular() || lu.determinant() != 0.0 {
+        return SolverError::invalid_solution(format!("Expected a singular matrix, got {:?}", lu));
+    }
+    for result in &[lu.solve(&[1.0, 2.0, 3.0]).map(|_| ()), lu.inverse().map(|_| ()),
+                    lu.solve_matrix(&Matrix::identity(3)).map(|_| ())] {
+        match result {
+            Err(ref e) if e.kind() == ErrorKind::Underspecified => (),
+            result => return SolverError::invalid_solution(format!("Expected Underspecified, \
+                got {:?}", result)),
+        }
+    }
+
+    match LuDecomposition::new(&Matrix::zeros(3, 2), PIVOT) {
+        Err(ref e) if e.kind() == ErrorKind::InvalidMatrix => (),
+        result => return SolverError::invalid_solution(format!("Expected InvalidMatrix, got \
+            {:?}", result)),
+    }
+
+    let lu = LuDecomposition::new(&pivoting_matrix()?, PIVOT)?;
+    let wrong_rhs = Matrix::zeros(1, 2);
+    if lu.solve(&[1.0, 2.0]).is_ok() || lu.solve_matrix(&wrong_rhs).is_ok() {
+        return SolverError::invalid_solution("Expected errors for the wrong right hand side size");
+    }
+
+    // decomposes the current sub-view
+    let mut matrix = Matrix::new(4, 4, vec![9.0, 9.0, 9.0, 9.0,
+                                            9.0, 0.0, 2.0, 1.0,
+                                            9.0, 1.0, 1.0, 1.0,
+                                            9.0, 4.0, -1.0, 3.0])?;
+    matrix.sub(1, 1)?;
+    let x = LuDecomposition::new(&matrix, PIVOT)?.solve(&[7.0, 6.0, 11.0])?;
+    if !values_eq(&[1.0, 2.0, 3.0], &x, TOLERANCE) ||
+        matrix.value(Row::new(1), Col::new(1)) != 0.0 {
+        return SolverError::invalid_solution(format!("Expected [1, 2, 3], got {:?}", x));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,302 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use rusolve::{CsrMatrix, CscMatrix, Matrix, Row, Col, Result, SolverError, ErrorKind};
+
+mod common;
+use crate::common::setup;
+
+fn row(index: usize) -> Row {
+    Row::new(index)
+}
+
+fn col(index: usize) -> Col {
+    Col::new(index)
+}
+
+fn dense_eq(expected: &[f64], matrix: &Matrix) -> bool {
+    let found: Vec<f64> = matrix.rows()
+        .flat_map(|row| matrix.cols().map(move |col| (row, col)))
+        .map(|(row, col)| matrix.value(row, col)).collect();
+    expected == found.as_slice()
+}
+
+/// A 3 x 4 matrix with a few zero entries, and its dense values
+fn sparse_matrix() -> Result<(CsrMatrix, Vec<f64>)> {
+    let entries = vec![(0, 0, 1.0), (2, 3, 4.0), (1, 1, 2.0), (0, 2, -3.0), (2, 0, 5.0),
+                       (1, 3, 1.5), (1, 3, 0.5), (0, 1, 7.0), (0, 1, -7.0)];
+    let dense = vec![1.0, 0.0, -3.0, 0.0,
+                     0.0, 2.0,  0.0, 2.0,
+                     5.0, 0.0,  0.0, 4.0];
+    Ok((CsrMatrix::from_entries(4, 3, entries)?, dense))
+}
+
+#[test]
+fn sparse_dense_conversion() -> Result<()> {
+    setup()?;
+
+    let (csr, dense) = sparse_matrix()?;
+    if csr.width() != 4 || csr.height() != 3 || csr.nnz() != 6 {
+        return SolverError::invalid_solution(format!("Expected 6 entries, got {:?}", csr));
+    }
+
+    let matrix = csr.to_dense();
+    if !dense_eq(&dense, &matrix) || !dense_eq(&dense, &csr.to_csc().to_dense()) {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", dense, matrix));
+    }
+
+    if CsrMatrix::from_dense(&matrix) != csr || CscMatrix::from_dense(&matrix) != csr.to_csc() {
+        return SolverError::invalid_solution("Conversion from dense did not match");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sparse_iteration() -> Result<()> {
+    setup()?;
+
+    let (csr, _) = sparse_matrix()?;
+    let entries: Vec<(usize, f64)> = csr.row(row(0)).map(|(col, value)| (col.index(), value))
+        .collect();
+    if entries != vec![(0, 1.0), (2, -3.0)] {
+        return SolverError::invalid_solution(format!("Expected row 0 entries, got {:?}", entries));
+    }
+
+    let csc = csr.to_csc();
+    let entries: Vec<(usize, f64)> = csc.col(col(3)).map(|(row, value)| (row.index(), value))
+        .collect();
+    if entries != vec![(1, 2.0), (2, 4.0)] {
+        return SolverError::invalid_solution(format!("Expected col 3 entries, got {:?}", entries));
+    }
+
+    for row in csr.rows() {
+        for col in csr.cols() {
+            if csr.value(row, col) != csc.value(row, col) {
+                return SolverError::invalid_solution(format!("Mismatch at {:?}, {:?}", row, col));
+            }
+        }
+    }
+
+    if csc.to_csr() != csr || csc.col(col(1)).count() != 1 {
+        return SolverError::invalid_solution(format!("Unexpected transpose {:?}", csc));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sparse_row_operations() -> Result<()> {
+    setup()?;
+
+    let (mut csr, _) = sparse_matrix()?;
+    let mut matrix = csr.to_dense();
+
+    csr.swap_rows(row(0), row(2));
+    matrix.swap_rows(row(0), row(2));
+    csr.multiply_row(row(1), -2.0);
+    matrix.multiply_row(row(1), -2.0);
+    csr.add_row(row(1), row(0), 1.0);
+    matrix.add_row(row(1), row(0), 1.0);
+    csr.add_row(row(0), row(2), 0.5);
+    matrix.add_row(row(0), row(2), 0.5);
+
+    if CsrMatrix::from_dense(&matrix) != csr {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", matrix, csr));
+    }
+
+    // the entries of row 0 in column 3 cancel exactly
+    if csr.value(row(0), col(3)) != 0.0 || csr.row(row(0)).any(|(col, _)| col.index() == 3) {
+        return SolverError::invalid_solution(format!("Expected no entry at (0, 3) in {:?}", csr));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sparse_set_value() -> Result<()> {
+    setup()?;
+
+    let mut csr = CsrMatrix::new(3, 2);
+    csr.set_value(row(1), col(2), 4.0);
+    csr.set_value(row(0), col(1), 2.0);
+    csr.set_value(row(1), col(0), 3.0);
+    csr.set_value(row(1), col(2), 0.0);
+    let expected = CsrMatrix::from_entries(3, 2, vec![(0, 1, 2.0), (1, 0, 3.0)])?;
+    if csr != expected {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, csr));
+    }
+
+    let mut csc = CscMatrix::new(3, 2);
+    csc.set_value(row(1), col(0), 3.0);
+    csc.set_value(row(0), col(1), 2.0);
+    if csc != expected.to_csc() {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, csc));
+    }
+
+    Ok(())
+}
+
+fn expect_invalid<T: std::fmt::Debug>(result: Result<T>) -> Result<()> {
+    match result {
+        Err(ref e) if e.kind() == ErrorKind::InvalidMatrix => Ok(()),
+        _ => SolverError::invalid_solution(format!("Expected InvalidMatrix, got {:?}", result)),
+    }
+}
+
+#[test]
+fn matrix_construction() -> Result<()> {
+    setup()?;
+
+    let matrix = Matrix::new(3, 2, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0])?;
+    if matrix.width() != 3 || matrix.height() != 2 || matrix.value(row(1), col(0)) != 4.0 {
+        return SolverError::invalid_solution(format!("Unexpected matrix {:?}", matrix));
+    }
+    expect_invalid(Matrix::new(3, 2, vec![1.0; 5]))?;
+    expect_invalid(Matrix::from_entries(2, 2, vec![(0, 2, 1.0)]))?;
+    expect_invalid(CsrMatrix::from_entries(2, 2, vec![(2, 0, 1.0)]))?;
+    expect_invalid(CscMatrix::from_entries(2, 2, vec![(0, 2, 1.0)]))?;
+
+    let summed = Matrix::from_entries(2, 1, vec![(0, 1, 1.5), (0, 1, 2.0)])?;
+    if !dense_eq(&[0.0, 3.5], &summed) {
+        return SolverError::invalid_solution(format!("Expected summed entries, got {:?}",
+                                                     summed));
+    }
+
+    if !dense_eq(&[1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0], &Matrix::identity(3)) ||
+        !dense_eq(&[0.0; 6], &Matrix::zeros(2, 3)) {
+        return SolverError::invalid_solution("Unexpected identity or zeros matrix");
+    }
+
+    // small and empty matrices are allowed
+    let matrix = Matrix::new(1, 1, vec![2.5])?;
+    let empty = Matrix::zeros(0, 0);
+    if matrix.value(row(0), col(0)) != 2.5 || empty.width() != 0 || empty.rows().count() != 0 {
+        return SolverError::invalid_solution("Unexpected small matrices");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn matrix_products() -> Result<()> {
+    setup()?;
+
+    let a = Matrix::new(3, 2, vec![1.0, 2.0, 3.0,
+                                   4.0, 5.0, 6.0])?;
+    let b = Matrix::new(2, 3, vec![1.0, 0.0,
+                                   0.0, 1.0,
+                                   2.0, -1.0])?;
+
+    if !dense_eq(&[1.0, 4.0, 2.0, 5.0, 3.0, 6.0], &a.transpose()) {
+        return SolverError::invalid_solution(format!("Unexpected transpose {:?}", a.transpose()));
+    }
+
+    let product = a.mul_vec(&[1.0, -1.0, 2.0])?;
+    if product != vec![5.0, 11.0] {
+        return SolverError::invalid_solution(format!("Expected [5, 11], got {:?}", product));
+    }
+
+    let product = a.matmul(&b)?;
+    if !dense_eq(&[7.0, -1.0, 16.0, -1.0], &product) {
+        return SolverError::invalid_solution(format!("Unexpected product {:?}", product));
+    }
+
+    let product = Matrix::identity(2).matmul(&a)?;
+    if !dense_eq(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0], &product) {
+        return SolverError::invalid_solution(format!("Unexpected product {:?}", product));
+    }
+
+    expect_invalid(a.mul_vec(&[1.0, 2.0]))?;
+    expect_invalid(a.matmul(&a))?;
+
+    Ok(())
+}
+
+#[test]
+fn matrix_elementwise() -> Result<()> {
+    setup()?;
+
+    let a = Matrix::new(2, 2, vec![1.0, 2.0, 3.0, 4.0])?;
+    let b = Matrix::new(2, 2, vec![4.0, 3.0, 2.0, 1.0])?;
+
+    if !dense_eq(&[5.0; 4], &a.add(&b)?) ||
+        !dense_eq(&[-3.0, -1.0, 1.0, 3.0], &a.subtract(&b)?) ||
+        !dense_eq(&[4.0, 6.0, 6.0, 4.0], &a.hadamard(&b)?) ||
+        !dense_eq(&[2.0, 4.0, 6.0, 8.0], &a.scale(2.0)) ||
+        !dense_eq(&[1.0, 4.0, 9.0, 16.0], &a.map(|value| value * value)) {
+        return SolverError::invalid_solution("Unexpected element-wise result");
+    }
+
+    expect_invalid(a.add(&Matrix::zeros(1, 2)))?;
+    expect_invalid(a.hadamard(&Matrix::zeros(2, 1)))?;
+
+    Ok(())
+}
+
+#[test]
+fn matrix_views() -> Result<()> {
+    setup()?;
+
+    let mut matrix = Matrix::new(3, 3, vec![1.0, 2.0, 3.0,
+                                            4.0, 5.0, 6.0,
+                                            7.0, 8.0, 9.0])?;
+
+    let view = matrix.view(row(1), col(1), 2, 2)?;
+    if view.value(row(0), col(1)) != 6.0 || !dense_eq(&[5.0, 6.0, 8.0, 9.0], &view.to_matrix()) {
+        return SolverError::invalid_solution(format!("Unexpected view {:?}", view));
+    }
+    expect_invalid(matrix.view(row(2), col(0), 3, 2))?;
+
+    // operations apply to the current sub-view
+    matrix.sub(1, 0)?;
+    if !dense_eq(&[2.0, 5.0, 8.0, 3.0, 6.0, 9.0], &matrix.transpose()) {
+        return SolverError::invalid_solution(format!("Unexpected transpose {:?}", matrix));
+    }
+    expect_invalid(matrix.view(row(0), col(0), 1, 1))?;
+    expect_invalid(matrix.sub(4, 0))?;
+
+    // handles still index the overall matrix
+    matrix.set_value(row(2), col(1), 10.0);
+    if matrix.value(row(0), col(2)) != 3.0 || matrix.first_col() != col(1) ||
+        !dense_eq(&[2.0, 3.0, 5.0, 6.0, 10.0, 9.0], &matrix.scale(1.0)) {
+        return SolverError::invalid_solution(format!("Unexpected values {:?}", matrix));
+    }
+
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "outside the view")]
+fn matrix_value_past_width() {
+    let matrix = Matrix::zeros(2, 2);
+    matrix.value(row(0), col(2));
+}
+
+#[test]
+#[should_panic(expected = "outside the view")]
+fn matrix_set_value_outside_sub() {
+    let mut matrix = Matrix::zeros(2, 2);
+    matrix.sub(1, 0).unwrap();
+    matrix.set_value(row(0), col(0), 1.0);
+}
+
+#[test]
+#[should_panic(expected = "outside the 1 x 1 view")]
+fn matrix_view_value_outside() {
+    let matrix = Matrix::zeros(2, 2);
+    matrix.view(row(0), col(0), 1, 1).unwrap().value(row(0), col(1));
+}
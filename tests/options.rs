@@ -0,0 +1,443 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use rusolve::{Constraint, ConstraintKind, Problem, Expression, ObjectiveKind, Result, ErrorKind,
+    SolverError, SolveStatus, SolverOptions, Algorithm, Tolerances, VariableKind, Progress,
+    ProgressAction, ProgressCallback, create_constraints, create_expr, create_constraint};
+
+mod common;
+use crate::common::setup;
+
+#[test]
+fn options_iteration_limit() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [ 3.0, 2.0, 1.0 ;le; 10.0],
+            [ 2.0, 5.0, 3.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0, 4.0), ObjectiveKind::Maximize);
+
+    let options = SolverOptions { iteration_limit: Some(1), ..SolverOptions::default() };
+    let result = problem.solve_detailed_with(&options)?;
+    // phase II starts from a feasible basis, so its solution is returned
+    if result.status() != SolveStatus::Feasible || result.stats().iterations() != 1 {
+        return SolverError::invalid_solution(format!("Expected a feasible solution at the \
+            iteration limit, got {:?}", result));
+    }
+
+    match problem.solve_with(&options) {
+        Err(e) if e.kind() == ErrorKind::UnableToSolve => Ok(()),
+        _ => SolverError::invalid_solution("Expected solve to fail at the iteration limit"),
+    }
+}
+
+#[test]
+fn options_forced_simplex() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(2);
+    problem.add_constraints(create_constraints!(
+            [2.0, 2.0 ;le; 3.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 1.0), ObjectiveKind::Maximize);
+
+    if problem.solve()?.objective() != Some(1.0) {
+        return SolverError::invalid_solution("Expected integer objective of 1");
+    }
+
+    // the simplex method solves the continuous relaxation
+    let options = SolverOptions { algorithm: Some(Algorithm::Simplex), ..SolverOptions::default() };
+    match problem.solve_with(&options)?.objective() {
+        Some(objective) if (objective - 1.5).abs() < 1e-9 => Ok(()),
+        objective => SolverError::invalid_solution(format!("Expected relaxed objective of 1.5, \
+            got {:?}", objective)),
+    }
+}
+
+#[test]
+fn options_forced_gaussian_elimination() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;eq; 2.0],
+            [1.0, -1.0 ;eq; 0.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 1.0), ObjectiveKind::Maximize);
+
+    let options = SolverOptions {
+        algorithm: Some(Algorithm::GaussianElimination),
+        ..SolverOptions::default()
+    };
+    match problem.solve_with(&options) {
+        Err(e) if e.kind() == ErrorKind::InvalidObjective => Ok(()),
+        _ => SolverError::invalid_solution("Expected the objective to be rejected"),
+    }
+}
+
+#[test]
+fn options_forced_brute_force() -> Result<()> {
+    setup()?;
+
+    let options = SolverOptions { algorithm: Some(Algorithm::BruteForce), ..SolverOptions::default() };
+    let mut problem = Problem::new();
+    problem.set_objective(Expression::default(), ObjectiveKind::Minimize);
+    let result = problem.solve_detailed_with(&options)?;
+    if result.status() != SolveStatus::Optimal {
+        return SolverError::invalid_solution(format!("Expected Optimal, got {:?}", result));
+    }
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 2.0 ;le; 4.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 1.0), ObjectiveKind::Maximize);
+    match problem.solve_with(&options)?.objective() {
+        Some(objective) if (objective - 4.0).abs() < 1e-9 => Ok(()),
+        objective => SolverError::invalid_solution(format!("Expected objective of 4, got {:?}",
+                                                           objective)),
+    }
+}
+
+#[test]
+fn options_iteration_limit_brute_force() -> Result<()> {
+    setup()?;
+
+    // every assignment of x needs at least one simplex iteration for y and z
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Integer(0, 3));
+    let y = problem.add_variable(VariableKind::Continuous);
+    let z = problem.add_variable(VariableKind::Continuous);
+    problem.add_constraint((x + y + 2.0 * z).le(6.0))?;
+    problem.set_objective(x + y + z, ObjectiveKind::Maximize);
+
+    let options = SolverOptions { iteration_limit: Some(3), ..SolverOptions::default() };
+    let result = problem.solve_detailed_with(&options)?;
+    if result.status() != SolveStatus::Feasible || result.stats().iterations() > 3 {
+        return SolverError::invalid_solution(format!("Expected a feasible solution at the \
+            iteration limit, got {:?}", result));
+    }
+
+    let result = problem.solve_detailed_with(&SolverOptions::default())?;
+    if result.status() != SolveStatus::Optimal || result.stats().iterations() <= 3 {
+        return SolverError::invalid_solution(format!("Expected more than 3 iterations, got {:?}",
+                                                     result));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn options_iteration_limit_brute_force_no_incumbent() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Integer(0, 3));
+    let y = problem.add_variable(VariableKind::Continuous);
+    problem.add_constraint((x + y).le(6.0))?;
+    problem.set_objective(x + y, ObjectiveKind::Maximize);
+
+    let options = SolverOptions { iteration_limit: Some(0), ..SolverOptions::default() };
+    let result = problem.solve_detailed_with(&options)?;
+    if result.status() != SolveStatus::IterationLimit || result.solution().is_some() {
+        return SolverError::invalid_solution(format!("Expected iteration limit, got {:?}",
+                                                     result));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn options_feasibility_tolerance() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;le; 0.9999])
+    )?;
+    problem.set_objective(create_expr!(1.0, 2.0), ObjectiveKind::Maximize);
+
+    if problem.solve()?.objective() != Some(0.0) {
+        return SolverError::invalid_solution("Expected objective of 0 with default tolerances");
+    }
+
+    let tolerances = Tolerances { feasibility: 1e-3, ..Tolerances::default() };
+    let options = SolverOptions { tolerances, ..SolverOptions::default() };
+    if problem.solve_with(&options)?.objective() != Some(2.0) {
+        return SolverError::invalid_solution("Expected objective of 2 with a loose tolerance");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn options_pivot_tolerance() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;eq; 2.0],
+            [1.0, 1.0 + 1e-12 ;eq; 2.0])
+    )?;
+
+    match problem.solve() {
+        Err(e) if e.kind() == ErrorKind::Underspecified => (),
+        _ => return SolverError::invalid_solution("Expected a nearly singular system to be rejected"),
+    }
+
+    let tolerances = Tolerances { pivot: 0.0, ..Tolerances::default() };
+    let options = SolverOptions { tolerances, ..SolverOptions::default() };
+    problem.solve_with(&options)?;
+
+    Ok(())
+}
+
+fn large_integer_problem() -> Result<Problem> {
+    let mut problem = Problem::new();
+    let x = problem.add_variables(VariableKind::Integer(0, 20), 8);
+    let mut expr = Expression::default();
+    for (i, var) in x.iter().enumerate() {
+        expr.add_term(i as f64 + 1.0, *var);
+    }
+    problem.add_constraint(expr.clone().le(100.0))?;
+    problem.set_objective(expr, ObjectiveKind::Maximize);
+    Ok(problem)
+}
+
+#[test]
+fn options_time_limit() -> Result<()> {
+    setup()?;
+
+    let problem = large_integer_problem()?;
+    let options = SolverOptions {
+        time_limit: Some(Duration::from_millis(50)),
+        ..SolverOptions::default()
+    };
+    let result = problem.solve_detailed_with(&options)?;
+    if result.status() != SolveStatus::Feasible || result.stats().elapsed() > Duration::from_secs(5) {
+        return SolverError::invalid_solution(format!("Expected a feasible solution at the time \
+            limit, got {:?}", result));
+    }
+
+    let solution = match result.solution() {
+        None => return SolverError::invalid_solution("Expected an incumbent solution"),
+        Some(solution) => solution,
+    };
+    if !problem.verify(solution, &Tolerances::default())?.is_feasible() {
+        return SolverError::invalid_solution("Expected the incumbent to be feasible");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn options_cancel() -> Result<()> {
+    setup()?;
+
+    let problem = large_integer_problem()?;
+    let cancel = Arc::new(AtomicBool::new(false));
+    let options = SolverOptions { cancel: Some(Arc::clone(&cancel)), ..SolverOptions::default() };
+
+    let handle = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        cancel.store(true, Ordering::Relaxed);
+    });
+    let result = problem.solve_detailed_with(&options)?;
+    handle.join().unwrap();
+
+    if result.status() != SolveStatus::Feasible || result.solution().is_none() {
+        return SolverError::invalid_solution(format!("Expected a feasible solution when \
+            cancelled, got {:?}", result.status()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn options_cancel_simplex() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [ 3.0, 2.0, 1.0 ;le; 10.0],
+            [ 2.0, 5.0, 3.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0, 4.0), ObjectiveKind::Maximize);
+
+    // the initial basis is feasible, so it is returned as the incumbent
+    let options = SolverOptions {
+        cancel: Some(Arc::new(AtomicBool::new(true))),
+        ..SolverOptions::default()
+    };
+    let result = problem.solve_detailed_with(&options)?;
+    match result.solution() {
+        Some(solution) if result.status() == SolveStatus::Feasible &&
+            solution.objective() == Some(0.0) => (),
+        _ => return SolverError::invalid_solution(format!("Expected interruption, got {:?}",
+                                                          result)),
+    }
+
+    match problem.solve_with(&options) {
+        Err(e) if e.kind() == ErrorKind::UnableToSolve => Ok(()),
+        _ => SolverError::invalid_solution("Expected solve to fail when interrupted"),
+    }
+}
+
+#[test]
+fn options_time_limit_gaussian_elimination() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;eq; 2.0],
+            [1.0, -1.0 ;eq; 0.0])
+    )?;
+
+    let options = SolverOptions { time_limit: Some(Duration::from_secs(0)), ..SolverOptions::default() };
+    let result = problem.solve_detailed_with(&options)?;
+    if result.status() != SolveStatus::TimeLimit || result.solution().is_some() {
+        return SolverError::invalid_solution(format!("Expected time limit, got {:?}", result));
+    }
+
+    Ok(())
+}
+
+fn recording_options(action: ProgressAction) -> (SolverOptions, Arc<Mutex<Vec<Progress>>>) {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&reports);
+    let progress = ProgressCallback::new(move |progress| {
+        recorded.lock().unwrap().push(*progress);
+        action
+    });
+
+    (SolverOptions { progress: Some(progress), ..SolverOptions::default() }, reports)
+}
+
+#[test]
+fn options_progress_simplex() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [ 1.0, 1.0, 1.0 ;ge; 2.0],
+            [ 3.0, 2.0, 1.0 ;le; 10.0],
+            [ 2.0, 5.0, 3.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0, 4.0), ObjectiveKind::Maximize);
+
+    let (options, reports) = recording_options(ProgressAction::Continue);
+    let result = problem.solve_detailed_with(&options)?;
+    let reports = reports.lock().unwrap();
+
+    let mut phases = Vec::new();
+    for (index, report) in reports.iter().enumerate() {
+        match report {
+            Progress::Simplex { iteration, phase, .. } if *iteration == index as u64 + 1 =>
+                phases.push(*phase),
+            _ => return SolverError::invalid_solution(format!("Unexpected report {:?}", report)),
+        }
+    }
+
+    if reports.len() as u64 != result.stats().iterations() || phases.first() != Some(&1) ||
+        phases.last() != Some(&2) {
+        return SolverError::invalid_solution(format!("Unexpected reports {:?}", reports));
+    }
+
+    match reports.last() {
+        Some(Progress::Simplex { objective, .. }) if Some(*objective) ==
+            result.solution().and_then(|solution| solution.objective()) => Ok(()),
+        _ => SolverError::invalid_solution("Expected the final report to have the optimal objective"),
+    }
+}
+
+#[test]
+fn options_progress_incumbent() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_variables(VariableKind::Integer(0, 3), 3);
+    problem.add_constraint((x[0] + x[1] + x[2]).le(4.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1] + 3.0 * x[2], ObjectiveKind::Maximize);
+
+    let (options, reports) = recording_options(ProgressAction::Continue);
+    let solution = problem.solve_with(&options)?;
+    let reports = reports.lock().unwrap();
+
+    let mut best = f64::MIN;
+    for report in reports.iter() {
+        match report {
+            Progress::Incumbent { objective, .. } if *objective > best => best = *objective,
+            _ => return SolverError::invalid_solution(format!("Unexpected report {:?}", report)),
+        }
+    }
+
+    if Some(best) != solution.objective() {
+        return SolverError::invalid_solution(format!("Expected last incumbent {} to be optimal",
+                                                     best));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn options_progress_terminate() -> Result<()> {
+    setup()?;
+
+    let problem = large_integer_problem()?;
+    let (options, reports) = recording_options(ProgressAction::Terminate);
+    let result = problem.solve_detailed_with(&options)?;
+
+    if result.status() != SolveStatus::Feasible || result.stats().nodes() != 1 {
+        return SolverError::invalid_solution(format!("Expected interruption at the first node, \
+            got {:?}", result));
+    }
+
+    let reports = reports.lock().unwrap();
+    match reports.as_slice() {
+        [Progress::Incumbent { nodes: 1, objective }] if *objective == 0.0 => Ok(()),
+        reports => SolverError::invalid_solution(format!("Unexpected reports {:?}", reports)),
+    }
+}
+
+#[test]
+fn options_progress_nodes() -> Result<()> {
+    setup()?;
+
+    let problem = large_integer_problem()?;
+    let progress = ProgressCallback::new(|progress| {
+        match progress {
+            Progress::Nodes { .. } => ProgressAction::Terminate,
+            _ => ProgressAction::Continue,
+        }
+    });
+    let options = SolverOptions { progress: Some(progress), ..SolverOptions::default() };
+    let result = problem.solve_detailed_with(&options)?;
+
+    if result.status() != SolveStatus::Feasible || result.stats().nodes() != 10_000 ||
+        result.solution().is_none() {
+        return SolverError::invalid_solution(format!("Expected interruption with an incumbent \
+            after 10000 nodes, got {:?}", result));
+    }
+
+    Ok(())
+}
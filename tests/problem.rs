@@ -0,0 +1,368 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2019 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use rusolve::{Problem, Expression, ObjectiveKind, Result, ErrorKind, Variable, VariableKind,
+    SolverError, Solution};
+
+mod common;
+use crate::common::{setup, solution_err};
+
+#[test]
+fn problem_constraint_invalid_variable() -> Result<()> {
+    setup()?;
+
+    let other = Problem::continuous(5);
+    let invalid: Variable = *other.variables().last().unwrap();
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    match problem.add_constraint((x[0] + invalid).le(1.0)) {
+        Err(e) if e.kind() == ErrorKind::InvalidConstraint => Ok(()),
+        Err(e) => SolverError::invalid_solution(format!("Unexpected error {}", e)),
+        Ok(_) => SolverError::invalid_solution("Expected constraint to be rejected"),
+    }
+}
+
+#[test]
+fn problem_objective_invalid_variable() -> Result<()> {
+    setup()?;
+
+    let other = Problem::continuous(5);
+    let invalid: Variable = *other.variables().last().unwrap();
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint(Expression::from(x[0]).le(1.0))?;
+    problem.set_objective(x[0] + invalid, ObjectiveKind::Maximize);
+    solution_err(problem, ErrorKind::InvalidObjective)
+}
+
+#[test]
+fn problem_add_variables_indices() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let a = problem.add_variables(VariableKind::Integer(0, 1), 2);
+    let b = problem.add_variable(VariableKind::Integer(0, 3));
+    let c = problem.add_variables(VariableKind::Integer(0, 1), 2);
+
+    let indices: Vec<usize> = a.iter().chain(std::iter::once(&b)).chain(c.iter())
+        .map(|var| var.index()).collect();
+    if indices != vec![0, 1, 2, 3, 4] || problem.num_variables() != 5 {
+        return SolverError::invalid_solution(format!("Unexpected indices {:?}", indices));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_variable_handles() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let flags = problem.add_variables(VariableKind::Integer(0, 1), 2);
+    let count = problem.add_variable(VariableKind::Integer(0, 5));
+
+    let mut expr = Expression::default();
+    expr.add_term(1.0, count);
+    expr.add_term(-2.0, flags[0]);
+    expr.add_term(-2.0, flags[1]);
+    problem.add_constraint(expr.le(0.0))?;
+    problem.add_constraint((flags[0] + flags[1]).le(1.0))?;
+    problem.set_objective(count - 0.5 * flags[0] - 0.5 * flags[1], ObjectiveKind::Maximize);
+
+    let solution = problem.solve()?;
+    if solution.value_of(count) != 2.0 || solution.value_of(flags[0]) +
+        solution.value_of(flags[1]) != 1.0 {
+        return SolverError::invalid_solution(format!("Unexpected solution {:?}", solution));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_named_variables() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_named_variable("flow_a_b", VariableKind::Continuous)?;
+    let y = problem.add_variable(VariableKind::Continuous);
+    problem.set_variable_name(y, "flow_b_c")?;
+
+    if problem.variable("flow_a_b").map(|v| v.index()) != Some(0) ||
+        problem.variable("flow_b_c").map(|v| v.index()) != Some(1) ||
+        problem.variable("missing").is_some() || problem.variable_name(x) != Some("flow_a_b") {
+        return SolverError::invalid_solution("Variable name lookup failed");
+    }
+
+    match problem.add_named_variable("flow_a_b", VariableKind::Continuous) {
+        Err(e) if e.kind() == ErrorKind::InvalidVariable => (),
+        _ => return SolverError::invalid_solution("Expected duplicate name to be rejected"),
+    }
+
+    problem.add_constraint((x + y).le(10.0).named("capacity"))?;
+    problem.add_constraint((x + 2.0 * y).le(15.0))?;
+    problem.set_objective(2.0 * x + 3.0 * y, ObjectiveKind::Maximize);
+
+    if problem.constraint_index("capacity") != Some(0) {
+        return SolverError::invalid_solution("Constraint name lookup failed");
+    }
+
+    let solution = problem.solve()?;
+    if solution.value_by_name("flow_a_b") != Some(solution.value_of(x)) ||
+        solution.value_by_name("missing").is_some() || solution.name(1) != Some("flow_b_c") {
+        return SolverError::invalid_solution(format!("Unexpected solution {:?}", solution));
+    }
+
+    let debug = format!("{:?}", solution);
+    if !debug.contains("flow_a_b = ") || !debug.contains("flow_b_c = ") {
+        return SolverError::invalid_solution(format!("Names missing from {}", debug));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_named_constraint_errors() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1]).le(1.0).named("cap"))?;
+
+    match problem.add_constraint((x[0] - x[1]).ge(0.0).named("cap")) {
+        Err(e) if e.kind() == ErrorKind::InvalidConstraint => (),
+        _ => return SolverError::invalid_solution("Expected duplicate name to be rejected"),
+    }
+
+    let other = Problem::continuous(5);
+    let invalid: Variable = *other.variables().last().unwrap();
+    match problem.add_constraint((x[0] + invalid).le(1.0).named("bad_row")) {
+        Err(e) if e.message().contains("'bad_row'") => Ok(()),
+        _ => SolverError::invalid_solution("Expected error to name the constraint"),
+    }
+}
+
+#[test]
+fn problem_invalid_names() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    for name in ["x0", "x12", "", "2nd", ".flow", "flow a", "flow+b", "a:b"].iter() {
+        match problem.add_named_variable(*name, VariableKind::Continuous) {
+            Err(e) if e.kind() == ErrorKind::InvalidVariable => (),
+            result => return SolverError::invalid_solution(format!("Expected '{}' to be \
+                rejected, got {:?}", name, result)),
+        }
+    }
+    if problem.num_variables() != 0 {
+        return SolverError::invalid_solution("Rejected variables were added");
+    }
+
+    // names only need to avoid the exact form used for unnamed items
+    let x = problem.add_named_variable("x", VariableKind::Continuous)?;
+    let y = problem.add_named_variable("x1a", VariableKind::Continuous)?;
+    problem.set_variable_name(y, "c1")?;
+    if problem.set_variable_name(x, "x7").is_ok() || problem.variable_name(x) != Some("x") {
+        return SolverError::invalid_solution("Expected 'x7' to be rejected");
+    }
+
+    for name in ["c0", "obj", "cap limit"].iter() {
+        match problem.add_constraint((x + y).le(1.0).named(*name)) {
+            Err(e) if e.kind() == ErrorKind::InvalidConstraint => (),
+            result => return SolverError::invalid_solution(format!("Expected '{}' to be \
+                rejected, got {:?}", name, result)),
+        }
+    }
+    problem.add_constraint((x + y).le(1.0).named("x0"))?;
+
+    Ok(())
+}
+
+#[test]
+fn problem_display() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_named_variable("x", VariableKind::Continuous)?;
+    let y = problem.add_variable(VariableKind::Continuous);
+    let z = problem.add_named_variable("z", VariableKind::Integer(0, 3))?;
+    problem.add_constraint((x - y + 2.5 * z).le(10.0).named("cap"))?;
+    problem.add_constraint((-x + y).ge(-5.0))?;
+    problem.set_objective(2.0 * x + 3.0 * y - z, ObjectiveKind::Maximize);
+
+    let expected = "Maximize\n obj: 2 x + 3 x1 - z\nSubject To\n cap: x - x1 + 2.5 z <= 10\n \
+        c1: -x + x1 >= -5\nBounds\n 0 <= z <= 3\nGeneral\n z\nEnd\n";
+    let found = format!("{}", problem);
+    if found != expected {
+        return SolverError::invalid_solution(format!("Expected\n{}got\n{}", expected, found));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_bounds() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_named_variable("x", VariableKind::Continuous)?;
+    let y = problem.add_variable(VariableKind::Continuous);
+    let z = problem.add_variable(VariableKind::Integer(-1, 2));
+
+    if problem.bounds(x) != (0.0, f64::INFINITY) || problem.bounds(z) != (-1.0, 2.0) {
+        return SolverError::invalid_solution("Unexpected default bounds");
+    }
+
+    problem.set_bounds(x, -5.0, 10.0)?;
+    problem.set_bounds(y, f64::NEG_INFINITY, f64::INFINITY)?;
+    if problem.bounds(x) != (-5.0, 10.0) {
+        return SolverError::invalid_solution("Bounds were not set");
+    }
+
+    let invalid = [(z, 0.0, 1.0), (x, 2.0, 1.0), (x, f64::INFINITY, f64::INFINITY)];
+    for (var, lower, upper) in invalid.iter().cloned() {
+        match problem.set_bounds(var, lower, upper) {
+            Err(e) if e.kind() == ErrorKind::InvalidVariable => (),
+            _ => return SolverError::invalid_solution(
+                format!("Expected bounds [{}, {}] to be rejected", lower, upper)),
+        }
+    }
+
+    problem.set_objective(x + y + z, ObjectiveKind::Minimize);
+    let expected = "Minimize\n obj: x + x1 + x2\nSubject To\nBounds\n -5 <= x <= 10\n x1 free\n \
+        -1 <= x2 <= 2\nGeneral\n x2\nEnd\n";
+    let found = format!("{}", problem);
+    if found != expected {
+        return SolverError::invalid_solution(format!("Expected\n{}got\n{}", expected, found));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_range_constraint() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + 2.0 * x[1]).range(-1.0, 4.0).named("band"))?;
+
+    match problem.add_constraint((x[0] + x[1]).range(3.0, 2.0)) {
+        Err(e) if e.kind() == ErrorKind::InvalidConstraint => (),
+        _ => return SolverError::invalid_solution("Expected empty range to be rejected"),
+    }
+
+    problem.set_objective(x[0] + x[1], ObjectiveKind::Maximize);
+    let expected = "Maximize\n obj: x0 + x1\nSubject To\n band: -1 <= x0 + 2 x1 <= 4\nEnd\n";
+    let found = format!("{}", problem);
+    if found != expected {
+        return SolverError::invalid_solution(format!("Expected\n{}got\n{}", expected, found));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_constraint_activities() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    let first = problem.add_constraint(Expression::from(x[0]).le(4.0))?;
+    let second = problem.add_constraint((2.0 * x[1]).le(12.0))?;
+    let third = problem.add_constraint((3.0 * x[0] + 2.0 * x[1]).range(10.0, 18.0))?;
+    problem.set_objective(3.0 * x[0] + 5.0 * x[1], ObjectiveKind::Maximize);
+
+    let solution = problem.solve()?;
+    let expected = [(first, 2.0, 2.0, false), (second, 12.0, 0.0, true), (third, 18.0, 0.0, true)];
+    for (index, (constraint, activity, slack, binding)) in expected.iter().enumerate() {
+        if (solution.activity(*constraint).unwrap() - activity).abs() > 1e-6 ||
+            (solution.slack(index).unwrap() - slack).abs() > 1e-6 ||
+            solution.is_binding(*constraint) != Some(*binding) {
+            return SolverError::invalid_solution(format!("Unexpected activity {:?}, slack {:?} \
+                for constraint {}", solution.activity(index), solution.slack(index), index));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_constraint_activities_integer() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    let cap = problem.add_constraint((x[0] + x[1] + x[2]).le(2.0))?;
+    let min = problem.add_constraint((x[0] + x[1]).ge(0.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1] + 3.0 * x[2], ObjectiveKind::Maximize);
+
+    let solution = problem.solve()?;
+    if solution.activity(cap) != Some(2.0) || solution.is_binding(cap) != Some(true) ||
+        solution.slack(min) != Some(1.0) || solution.is_binding(min) != Some(false) {
+        return SolverError::invalid_solution(format!("Unexpected activities {:?}",
+            solution.activities().collect::<Vec<_>>()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_constraint_activities_equations() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraints(vec![(x[0] + x[1]).eq(3.0), (x[0] - x[1]).eq(1.0)])?;
+
+    let solution = problem.solve()?;
+    let activities: Vec<f64> = solution.activities().cloned().collect();
+    if activities != vec![3.0, 1.0] || solution.is_binding(0) != Some(true) ||
+        solution.is_binding(1) != Some(true) {
+        return SolverError::invalid_solution(format!("Unexpected activities {:?}", activities));
+    }
+
+    // a solution built by hand covers no constraints
+    let solution = Solution::new(vec![2.0, 1.0], None);
+    if solution.activity(0).is_some() || solution.slack(0).is_some() ||
+        solution.is_binding(0).is_some() {
+        return SolverError::invalid_solution("Expected no activities for a new solution");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn problem_set_constant() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    let cap = problem.add_constraint((x[0] + x[1]).le(4.0))?;
+    let band = problem.add_constraint((x[0] - x[1]).range(-1.0, 1.0))?;
+    problem.set_objective(x[0] + x[1], ObjectiveKind::Maximize);
+
+    problem.set_constant(cap, 2.0)?;
+    if problem.solve()?.objective() != Some(2.0) {
+        return SolverError::invalid_solution("Expected objective 2 after changing the constant");
+    }
+
+    match problem.set_constant(band, -2.0) {
+        Err(e) if e.kind() == ErrorKind::InvalidConstraint => Ok(()),
+        _ => SolverError::invalid_solution("Expected an empty range to be rejected"),
+    }
+}
@@ -0,0 +1,202 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::f64;
+
+use rusolve::{Constraint, ConstraintKind, Problem, Expression, ObjectiveKind, Result, Variable,
+    SolverError, SolverOptions, SolveStatus, Algorithm, PricingRule, Tolerances,
+    create_constraints, create_expr, create_constraint};
+
+mod common;
+use crate::common::{setup, solve_both};
+
+const TOLERANCE: f64 = 1e-6;
+
+fn revised_options() -> SolverOptions {
+    SolverOptions { algorithm: Some(Algorithm::RevisedSimplex), ..SolverOptions::default() }
+}
+
+/// Solves the problem with both the tableau and the revised simplex methods, checking
+/// that they produce the same solution, duals and basis
+fn solve_revised(problem: &Problem) -> Result<()> {
+    let result = solve_both(problem, &revised_options(), TOLERANCE)?;
+    let options = SolverOptions { algorithm: Some(Algorithm::Simplex), ..SolverOptions::default() };
+    let expected = problem.solve_with(&options)?;
+    let found = result.solution().unwrap();
+    if expected.basis() != found.basis() {
+        return SolverError::invalid_solution(format!("Expected basis {:?}, got {:?}",
+            expected.basis(), found.basis()));
+    }
+
+    Ok(())
+}
+
+/// A sparse problem with `size` constraints, each mentioning a few of the `3 * size / 2`
+/// variables
+fn sparse_problem(size: usize) -> Result<Problem> {
+    let num_variables = 3 * size / 2;
+    let mut problem = Problem::continuous(num_variables as u32);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    for i in 0..size {
+        let mut expr = 2.0 * x[i] + x[(7 * i + 3) % num_variables] +
+            3.0 * x[(13 * i + 5) % num_variables];
+        if i + size < num_variables {
+            expr.add_term(1.0, x[i + size]);
+        }
+        problem.add_constraint(expr.le(10.0 + (i % 7) as f64))?;
+    }
+
+    let mut objective = Expression::default();
+    for (i, var) in x.iter().enumerate() {
+        objective.add_term(1.0 + (i % 5) as f64, *var);
+    }
+    problem.set_objective(objective, ObjectiveKind::Maximize);
+    Ok(problem)
+}
+
+#[test]
+fn revised_simplex_matches_simplex() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [ 3.0, 2.0, 1.0 ;le; 10.0],
+            [ 2.0, 5.0, 3.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(-2.0, -3.0, -4.0), ObjectiveKind::Minimize);
+    solve_revised(&problem)?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, -1.0 ;ge; -5.0],
+            [1.0,  0.0 ;le; 10.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+    solve_revised(&problem)?;
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [3.0, 2.0, 1.0 ;eq; 10.0 ],
+            [2.0, 5.0, 3.0 ;eq; 15.0 ])
+    )?;
+    problem.set_objective(create_expr!(-2.0, -3.0, -4.0), ObjectiveKind::Minimize);
+    solve_revised(&problem)?;
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -5.0, f64::INFINITY)?;
+    problem.set_bounds(x[1], f64::NEG_INFINITY, 4.0)?;
+    problem.set_bounds(x[2], f64::NEG_INFINITY, f64::INFINITY)?;
+    problem.add_constraint((x[0] + x[2]).le(-2.0))?;
+    problem.add_constraint((x[1] - x[2]).ge(1.0))?;
+    problem.add_constraint(Constraint::new(x[0] + x[1], ConstraintKind::Range(-3.0), 3.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1] + x[2], ObjectiveKind::Maximize);
+    solve_revised(&problem)?;
+
+    Ok(())
+}
+
+#[test]
+fn revised_simplex_infeasible_unbounded() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;le; 2.0],
+            [1.0, 1.0 ;ge; 3.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 1.0), ObjectiveKind::Maximize);
+    let status = problem.solve_detailed_with(&revised_options())?.status();
+    if status != SolveStatus::Infeasible {
+        return SolverError::invalid_solution(format!("Expected Infeasible, got {:?}", status));
+    }
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, -1.0 ;le; -5.0],
+            [1.0,  0.0 ;le; 10.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+    let status = problem.solve_detailed_with(&revised_options())?.status();
+    if status != SolveStatus::Unbounded {
+        return SolverError::invalid_solution(format!("Expected Unbounded, got {:?}", status));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn revised_simplex_refactor() -> Result<()> {
+    setup()?;
+
+    // enough iterations that the basis is factorized again several times
+    let problem = sparse_problem(300)?;
+    let expected = problem.solve()?.objective().unwrap();
+    let result = problem.solve_detailed_with(&revised_options())?;
+    if result.stats().iterations() < 200 {
+        return SolverError::invalid_solution(format!("Expected at least 200 iterations, got {}",
+                                                     result.stats().iterations()));
+    }
+
+    let solution = result.into_solution()?;
+    let report = problem.verify(&solution, &Tolerances::default())?;
+    if !report.is_feasible() || (solution.objective().unwrap() - expected).abs() > TOLERANCE {
+        return SolverError::invalid_solution(format!("Expected a feasible solution with \
+            objective {}, got {:?}", expected, report));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn revised_simplex_pricing_rules() -> Result<()> {
+    setup()?;
+
+    let problem = sparse_problem(100)?;
+    let expected = problem.solve()?.objective().unwrap();
+    for pricing in [PricingRule::Dantzig, PricingRule::SteepestEdge, PricingRule::Devex,
+                    PricingRule::Partial].iter() {
+        let options = SolverOptions { pricing: *pricing, ..revised_options() };
+        let objective = problem.solve_with(&options)?.objective().unwrap();
+        if (objective - expected).abs() > TOLERANCE {
+            return SolverError::invalid_solution(format!("Expected objective {} with {:?}, got {}",
+                                                         expected, pricing, objective));
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn revised_simplex_sensitivity() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;le; 10.0],
+            [1.0, 2.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+
+    let options = revised_options();
+    let expected = problem.sensitivity(&problem.solve()?, &Tolerances::default())?;
+    let found = problem.sensitivity(&problem.solve_with(&options)?, &options.tolerances)?;
+    if expected.costs() != found.costs() || expected.constants() != found.constants() {
+        return SolverError::invalid_solution(format!("Expected {:?}, got {:?}", expected, found));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,143 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use std::f64;
+
+use rusolve::{Constraint, ConstraintKind, Problem, Expression, ObjectiveKind, Result, ErrorKind,
+    SolverError, Ranging, Tolerances, create_constraints, create_expr, create_constraint};
+
+mod common;
+use crate::common::setup;
+
+fn ranging_eq(found: &Ranging, decrease: f64, increase: f64) -> Result<()> {
+    let close = |a: f64, b: f64| a == b || (a - b).abs() < 1e-6;
+    if !close(found.allowable_decrease(), decrease) || !close(found.allowable_increase(), increase) {
+        return SolverError::invalid_solution(format!("Expected decrease {} and increase {}, got {:?}",
+                                                     decrease, increase, found));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sensitivity_maximize() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [0.0, 2.0 ;le; 12.0],
+            [3.0, 2.0 ;le; 18.0])
+    )?;
+    problem.set_objective(create_expr!(3.0, 5.0), ObjectiveKind::Maximize);
+
+    let solution = problem.solve()?;
+    let sensitivity = problem.sensitivity(&solution, &Tolerances::default())?;
+
+    ranging_eq(sensitivity.cost(0), 3.0, 4.5)?;
+    ranging_eq(sensitivity.cost(1), 3.0, f64::INFINITY)?;
+    ranging_eq(sensitivity.constant(0), 2.0, f64::INFINITY)?;
+    ranging_eq(sensitivity.constant(1), 6.0, 6.0)?;
+    ranging_eq(sensitivity.constant(2), 6.0, 6.0)?;
+
+    if sensitivity.is_degenerate() {
+        return SolverError::invalid_solution("Expected a non-degenerate solution");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sensitivity_minimize() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;ge; 4.0],
+            [1.0, 3.0 ;ge; 6.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Minimize);
+
+    let solution = problem.solve()?;
+    let sensitivity = problem.sensitivity(&solution, &Tolerances::default())?;
+
+    ranging_eq(sensitivity.cost(0), 1.0, 1.0)?;
+    ranging_eq(sensitivity.cost(1), 1.0, 3.0)?;
+    ranging_eq(sensitivity.constant(0), 2.0, 2.0)?;
+    ranging_eq(sensitivity.constant(1), 2.0, 6.0)
+}
+
+#[test]
+fn sensitivity_bounded() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<_> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], 0.0, 2.0)?;
+    problem.add_constraint((x[0] + x[1]).le(5.0))?;
+    problem.set_objective(3.0 * x[0] + x[1], ObjectiveKind::Maximize);
+
+    // x0 stays at its upper bound as long as it is worth more than x1
+    let solution = problem.solve()?;
+    let sensitivity = problem.sensitivity(&solution, &Tolerances::default())?;
+    ranging_eq(sensitivity.cost_of(x[0]), 2.0, f64::INFINITY)?;
+    ranging_eq(sensitivity.cost_of(x[1]), 1.0, 2.0)?;
+    ranging_eq(sensitivity.constant(0), 3.0, f64::INFINITY)
+}
+
+#[test]
+fn sensitivity_degenerate() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [0.0, 2.0 ;le; 12.0],
+            [3.0, 2.0 ;le; 18.0],
+            [1.0, 1.0 ;le; 8.0])
+    )?;
+    problem.set_objective(create_expr!(3.0, 5.0), ObjectiveKind::Maximize);
+
+    let solution = problem.solve()?;
+    let sensitivity = problem.sensitivity(&solution, &Tolerances::default())?;
+    if !sensitivity.is_degenerate() {
+        return SolverError::invalid_solution("Expected a degenerate solution");
+    }
+
+    let degenerate = sensitivity.constants().iter().filter(|ranging| ranging.is_degenerate()).count();
+    if degenerate == 0 {
+        return SolverError::invalid_solution("Expected a degenerate constant ranging");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn sensitivity_no_basis() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;le; 1.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 2.0), ObjectiveKind::Maximize);
+
+    let solution = problem.solve()?;
+    match problem.sensitivity(&solution, &Tolerances::default()) {
+        Err(e) if e.kind() == ErrorKind::InvalidSolution => Ok(()),
+        _ => SolverError::invalid_solution("Expected sensitivity to require a basis"),
+    }
+}
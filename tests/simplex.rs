@@ -14,11 +14,14 @@
 //  You should have received a copy of the GNU General Public License
 //  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
 
+use std::f64;
+
 use rusolve::{Constraint, ConstraintKind, Problem, Expression, ObjectiveKind, Result, ErrorKind,
-    create_constraints, create_expr, create_constraint};
+    Variable, VariableKind, SolverError, SolverOptions, PricingRule, SolveStatus, create_constraints,
+    create_expr, create_constraint};
 
 mod common;
-use crate::common::{setup, solution_eq, solution_err};
+use crate::common::{setup, solution_eq, solution_err, duals_eq};
 
 #[test]
 fn simplex_neg_b() -> Result<()> {
@@ -30,7 +33,7 @@ fn simplex_neg_b() -> Result<()> {
             [1.0,  0.0 ;le; 10.0])
     )?;
     problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
-    solution_eq(problem, vec![10.0, 15.0], Some(65.0))
+    solution_err(problem, ErrorKind::Unbounded)
 }
 
 #[test]
@@ -81,5 +84,344 @@ fn simplex_unbounded() -> Result<()> {
             [1.0, 1.0, 1.0 ;ge; 0.0 ])
     )?;
     problem.set_objective(create_expr!(1.0, 1.0, 1.0), ObjectiveKind::Maximize);
+    solution_err(problem, ErrorKind::Unbounded)
+}
+
+#[test]
+fn simplex_sparse() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(6);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] + x[1]).le(4.0))?;
+    problem.add_constraint((x[2] + x[3]).le(6.0))?;
+    problem.add_constraint((x[4] + x[5]).le(2.0))?;
+    problem.set_objective(
+        x[0] + 2.0 * x[1] + 3.0 * x[2] + 4.0 * x[3] + 5.0 * x[4] + 6.0 * x[5],
+        ObjectiveKind::Maximize
+    );
+    solution_eq(problem, vec![0.0, 4.0, 0.0, 6.0, 0.0, 2.0], Some(44.0))
+}
+
+#[test]
+fn simplex_ge_positive() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;ge; 2.0],
+            [1.0, 0.0 ;ge; 0.5])
+    )?;
+    problem.set_objective(create_expr!(2.0, 1.0), ObjectiveKind::Minimize);
+    solution_eq(problem, vec![0.5, 1.5], Some(2.5))
+}
+
+#[test]
+fn simplex_neg_b_ge() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, -1.0 ;ge; -5.0],
+            [1.0,  0.0 ;le; 10.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Maximize);
+    solution_eq(problem, vec![10.0, 15.0], Some(65.0))
+}
+
+#[test]
+fn simplex_bounds_free() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -5.0, 10.0)?;
+    problem.set_bounds(x[1], f64::NEG_INFINITY, f64::INFINITY)?;
+    problem.add_constraint((x[0] + x[1]).ge(1.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1], ObjectiveKind::Minimize);
+    solution_eq(problem, vec![10.0, -9.0], Some(-8.0))
+}
+
+#[test]
+fn simplex_bounds_negative() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -5.0, f64::INFINITY)?;
+    problem.set_bounds(x[1], f64::NEG_INFINITY, 4.0)?;
+    problem.set_bounds(x[2], 3.0, 3.0)?;
+    problem.add_constraint(Expression::from(x[0]).le(-2.0))?;
+    problem.add_constraint((x[1] + x[2]).le(10.0))?;
+    problem.set_objective(x[0] + x[1] + x[2], ObjectiveKind::Maximize);
+    solution_eq(problem, vec![-2.0, 4.0, 3.0], Some(5.0))
+}
+
+#[test]
+fn simplex_bounds_only() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -5.0, 10.0)?;
+    problem.set_bounds(x[1], 2.0, f64::INFINITY)?;
+    problem.set_objective(x[0] + x[1], ObjectiveKind::Minimize);
+    solution_eq(problem, vec![-5.0, 2.0], Some(-3.0))
+}
+
+#[test]
+fn simplex_boxed_knapsack() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    for var in x.iter() {
+        problem.set_bounds(*var, 0.0, 1.0)?;
+    }
+    problem.add_constraint((10.0 * x[0] + 20.0 * x[1] + 30.0 * x[2]).le(50.0))?;
+    problem.set_objective(60.0 * x[0] + 100.0 * x[1] + 120.0 * x[2], ObjectiveKind::Maximize);
+    solution_eq(problem, vec![1.0, 1.0, 2.0 / 3.0], Some(240.0))
+}
+
+#[test]
+fn simplex_boxed_phase1() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    for var in x.iter() {
+        problem.set_bounds(*var, 0.0, 2.0)?;
+    }
+    problem.add_constraint((x[0] + x[1] + x[2]).ge(5.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1] + 3.0 * x[2], ObjectiveKind::Minimize);
+    solution_eq(problem, vec![2.0, 2.0, 1.0], Some(9.0))
+}
+
+#[test]
+fn simplex_boxed_negative() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], -3.0, -1.0)?;
+    problem.set_bounds(x[1], f64::NEG_INFINITY, 2.0)?;
+    problem.add_constraint((x[0] + x[1]).le(0.0))?;
+    problem.set_objective(2.0 * x[0] + x[1], ObjectiveKind::Maximize);
+    solution_eq(problem, vec![-1.0, 1.0], Some(-1.0))
+}
+
+#[test]
+fn simplex_boxed_infeasible() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], 0.0, 1.0)?;
+    problem.set_bounds(x[1], 0.0, 1.0)?;
+    problem.add_constraint((x[0] + x[1]).ge(3.0))?;
+    problem.set_objective(x[0] + x[1], ObjectiveKind::Minimize);
     solution_err(problem, ErrorKind::Infeasible)
 }
+
+#[test]
+fn simplex_range_lower() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] - x[1]).range(2.0, 4.0))?;
+    problem.add_constraint((x[0] + x[1]).le(10.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1], ObjectiveKind::Maximize);
+    solution_eq(problem, vec![6.0, 4.0], Some(14.0))
+}
+
+#[test]
+fn simplex_range_upper() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.add_constraint((x[0] - x[1]).range(2.0, 4.0))?;
+    problem.add_constraint(Expression::from(x[0]).ge(5.0))?;
+    problem.set_objective(x[0] + 2.0 * x[1], ObjectiveKind::Minimize);
+    solution_eq(problem, vec![5.0, 1.0], Some(7.0))
+}
+
+#[test]
+fn simplex_duals_maximize() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [0.0, 2.0 ;le; 12.0],
+            [3.0, 2.0 ;le; 18.0])
+    )?;
+    problem.set_objective(create_expr!(3.0, 5.0), ObjectiveKind::Maximize);
+    duals_eq(problem, vec![0.0, 1.5, 1.0], vec![0.0, 0.0])
+}
+
+#[test]
+fn simplex_duals_minimize() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [0.0, 2.0 ;le; 12.0],
+            [3.0, 2.0 ;le; 18.0])
+    )?;
+    problem.set_objective(create_expr!(-3.0, -5.0), ObjectiveKind::Minimize);
+    duals_eq(problem, vec![0.0, -1.5, -1.0], vec![0.0, 0.0])
+}
+
+#[test]
+fn simplex_duals_ge() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 1.0 ;ge; 4.0],
+            [1.0, 3.0 ;ge; 6.0])
+    )?;
+    problem.set_objective(create_expr!(2.0, 3.0), ObjectiveKind::Minimize);
+    duals_eq(problem, vec![1.5, 0.5], vec![0.0, 0.0])
+}
+
+#[test]
+fn simplex_reduced_costs() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Continuous);
+    let y = problem.add_variable(VariableKind::Continuous);
+    let z = problem.add_variable(VariableKind::Continuous);
+    problem.set_bounds(x, 0.0, 2.0)?;
+    problem.add_constraint((x + y + z).le(6.0))?;
+    problem.add_constraint((x - y).eq(-1.0))?;
+    problem.set_objective(3.0 * x + y - z, ObjectiveKind::Maximize);
+
+    // x is at its upper bound, and z is non-basic at zero
+    duals_eq(problem, vec![0.0, -1.0], vec![4.0, 0.0, -1.0])
+}
+
+#[test]
+fn simplex_duals_out_of_range() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, 0.0 ;le; 4.0],
+            [3.0, 2.0 ;le; 18.0])
+    )?;
+    problem.set_objective(create_expr!(3.0, 5.0), ObjectiveKind::Maximize);
+
+    let solution = problem.solve()?;
+    if solution.dual(1).is_none() || solution.dual(2).is_some() ||
+        solution.reduced_cost(1).is_none() || solution.reduced_cost(2).is_some() {
+        return SolverError::invalid_solution(format!("Expected a dual per constraint and a \
+            reduced cost per variable, got {:?} and {:?}", solution.duals(),
+            solution.reduced_costs()));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn simplex_cycling_beale() -> Result<()> {
+    setup()?;
+
+    // cycles with the most negative reduced cost rule and first index ratio test
+    let mut problem = Problem::continuous(4);
+    problem.add_constraints(create_constraints!(
+            [0.25, -60.0, -0.04, 9.0 ;le; 0.0],
+            [0.5,  -90.0, -0.02, 3.0 ;le; 0.0],
+            [0.0,    0.0,  1.0,  0.0 ;le; 1.0])
+    )?;
+    problem.set_objective(create_expr!(-0.75, 150.0, -0.02, 6.0), ObjectiveKind::Minimize);
+    solution_eq(problem, vec![0.04, 0.0, 1.0, 0.0], Some(-0.05))
+}
+
+#[test]
+fn simplex_cycling_kuhn() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(4);
+    problem.add_constraints(create_constraints!(
+            [-2.0,       -9.0,  1.0,        9.0 ;le; 0.0],
+            [1.0 / 3.0,   1.0, -1.0 / 3.0, -2.0 ;le; 0.0],
+            [2.0,         3.0, -1.0,      -12.0 ;le; 2.0])
+    )?;
+    problem.set_objective(create_expr!(-2.0, -3.0, 1.0, 12.0), ObjectiveKind::Minimize);
+
+    // the optimum is not unique, so only the objective is checked
+    solution_eq(problem, vec![], Some(-2.0))
+}
+
+const PRICING_RULES: [PricingRule; 4] = [PricingRule::Dantzig, PricingRule::SteepestEdge,
+    PricingRule::Devex, PricingRule::Partial];
+
+/// Solves the problem with each pricing rule, returning the iterations taken by each
+fn pricing_iterations(problem: &Problem, objective: f64) -> Result<Vec<u64>> {
+    let mut iterations = Vec::new();
+    for rule in PRICING_RULES.iter() {
+        let options = SolverOptions { pricing: *rule, ..SolverOptions::default() };
+        let result = problem.solve_detailed_with(&options)?;
+        match result.solution().and_then(|solution| solution.objective()) {
+            Some(found) if result.status() == SolveStatus::Optimal &&
+                (found - objective).abs() < 1e-6 => (),
+            found => return SolverError::invalid_solution(format!("Expected objective {} with \
+                {:?}, got {:?}", objective, rule, found)),
+        }
+        iterations.push(result.stats().iterations());
+    }
+
+    Ok(iterations)
+}
+
+#[test]
+fn simplex_pricing_rules() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(6);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    problem.set_bounds(x[0], f64::NEG_INFINITY, f64::INFINITY)?;
+    problem.set_bounds(x[1], -2.0, 3.0)?;
+    problem.set_bounds(x[2], 0.0, 4.0)?;
+    problem.add_constraint((x[0] + x[1] + x[2] + x[3]).le(10.0))?;
+    problem.add_constraint((x[0] - x[3] + 2.0 * x[4]).ge(2.0))?;
+    problem.add_constraint((x[2] + x[4] + x[5]).range(1.0, 6.0))?;
+    problem.add_constraint((x[0] - x[5]).eq(1.0))?;
+    problem.set_objective(2.0 * x[0] + x[1] + 3.0 * x[2] - x[3] + x[4] + 2.0 * x[5],
+                          ObjectiveKind::Maximize);
+
+    pricing_iterations(&problem, 29.0)?;
+    Ok(())
+}
+
+#[test]
+fn simplex_pricing_klee_minty() -> Result<()> {
+    setup()?;
+
+    // Dantzig's rule visits every vertex of the Klee-Minty cube
+    let n = 6;
+    let mut problem = Problem::continuous(n as u32);
+    let x: Vec<Variable> = problem.variables().cloned().collect();
+    let mut objective = Expression::default();
+    for i in 0..n {
+        let mut expr = Expression::from(x[i]);
+        for (j, var) in x.iter().enumerate().take(i) {
+            expr.add_term(2.0f64.powi((i - j + 1) as i32), *var);
+        }
+        problem.add_constraint(expr.le(5.0f64.powi(i as i32 + 1)))?;
+        objective.add_term(2.0f64.powi((n - 1 - i) as i32), x[i]);
+    }
+    problem.set_objective(objective, ObjectiveKind::Maximize);
+
+    let iterations = pricing_iterations(&problem, 15625.0)?;
+    if iterations[0] != 63 || iterations[1] != 1 || iterations[2] >= 63 {
+        return SolverError::invalid_solution(format!("Unexpected iterations {:?}", iterations));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use rusolve::{Constraint, ConstraintKind, Problem, Expression, ObjectiveKind, Result, ErrorKind,
+    SolverError, SolveStatus, create_constraints, create_expr, create_constraint};
+
+mod common;
+use crate::common::setup;
+
+#[test]
+fn solve_result_optimal() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(3);
+    problem.add_constraints(create_constraints!(
+            [ 3.0, 2.0, 1.0 ;le; 10.0],
+            [ 2.0, 5.0, 3.0 ;le; 15.0])
+    )?;
+    problem.set_objective(create_expr!(-2.0, -3.0, -4.0), ObjectiveKind::Minimize);
+
+    let result = problem.solve_detailed()?;
+    if result.status() != SolveStatus::Optimal {
+        return SolverError::invalid_solution(format!("Expected Optimal, got {:?}", result.status()));
+    }
+    if result.stats().iterations() == 0 {
+        return SolverError::invalid_solution("Expected at least one simplex iteration");
+    }
+    match result.solution().and_then(|solution| solution.objective()) {
+        Some(objective) if (objective + 20.0).abs() < 1e-6 => Ok(()),
+        objective => SolverError::invalid_solution(format!("Expected objective -20, got {:?}",
+                                                           objective)),
+    }
+}
+
+#[test]
+fn solve_result_unbounded() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::continuous(2);
+    problem.add_constraints(create_constraints!(
+            [1.0, -1.0 ;le; 1.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 1.0), ObjectiveKind::Maximize);
+
+    let result = problem.solve_detailed()?;
+    if result.status() != SolveStatus::Unbounded || result.solution().is_some() {
+        return SolverError::invalid_solution(format!("Expected Unbounded without a solution, \
+            got {:?}", result));
+    }
+
+    match result.into_solution() {
+        Err(e) if e.kind() == ErrorKind::Unbounded => Ok(()),
+        _ => SolverError::invalid_solution("Expected an Unbounded error"),
+    }
+}
+
+#[test]
+fn solve_result_infeasible() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(2);
+    problem.add_constraints(create_constraints!(
+        [1.0, 1.0 ;eq; 1.0],
+        [1.0, 1.0 ;eq; 2.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 1.0), ObjectiveKind::Maximize);
+
+    let result = problem.solve_detailed()?;
+    if result.status() != SolveStatus::Infeasible || result.solution().is_some() {
+        return SolverError::invalid_solution(format!("Expected Infeasible without a solution, \
+            got {:?}", result));
+    }
+
+    match result.into_solution() {
+        Err(e) if e.kind() == ErrorKind::Infeasible => Ok(()),
+        _ => SolverError::invalid_solution("Expected an Infeasible error"),
+    }
+}
+
+#[test]
+fn solve_result_brute_nodes() -> Result<()> {
+    setup()?;
+
+    let mut problem = Problem::boolean(3);
+    problem.add_constraints(create_constraints!(
+        [1.0, 1.0, 1.0 ;le; 2.0])
+    )?;
+    problem.set_objective(create_expr!(1.0, 2.0, 3.0), ObjectiveKind::Maximize);
+
+    let result = problem.solve_detailed()?;
+    if result.status() != SolveStatus::Optimal {
+        return SolverError::invalid_solution(format!("Expected Optimal, got {:?}", result.status()));
+    }
+    if result.stats().nodes() != 8 {
+        return SolverError::invalid_solution(format!("Expected 8 nodes, got {}",
+                                                     result.stats().nodes()));
+    }
+
+    let solution = result.into_solution()?;
+    if solution.objective() != Some(5.0) {
+        return SolverError::invalid_solution(format!("Expected objective 5, got {:?}",
+                                                     solution.objective()));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//  This file is part of rusolve, an optimizer / solver written in Rust.
+//  Copyright 2020 Jared Stephen
+//
+//  rusolve is free software: you can redistribute it and/or modify
+//  it under the terms of the GNU General Public License as published by
+//  the Free Software Foundation, either version 3 of the License, or
+//  (at your option) any later version.
+//
+//  rusolve is distributed in the hope that it will be useful,
+//  but WITHOUT ANY WARRANTY; without even the implied warranty of
+//  MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+//  GNU General Public License for more details.
+//
+//  You should have received a copy of the GNU General Public License
+//  along with rusolve.  If not, see <http://www.gnu.org/licenses/>
+
+use rusolve::{Problem, ObjectiveKind, Result, ErrorKind, Solution, SolverError,
+    Tolerances, Variable, VariableKind};
+
+mod common;
+use crate::common::setup;
+
+fn setup_problem() -> Result<(Problem, Vec<Variable>)> {
+    let mut problem = Problem::new();
+    let x = problem.add_variable(VariableKind::Continuous);
+    let y = problem.add_variable(VariableKind::Continuous);
+    let z = problem.add_variable(VariableKind::Integer(0, 3));
+    problem.set_bounds(y, -1.0, 1.0)?;
+    problem.add_constraint((x + y).le(4.0))?;
+    problem.add_constraint((x - z).ge(0.0))?;
+    problem.add_constraint((x + y + z).eq(3.0))?;
+    problem.set_objective(x + 2.0 * y + 3.0 * z, ObjectiveKind::Maximize);
+
+    Ok((problem, vec![x, y, z]))
+}
+
+#[test]
+fn verify_feasible() -> Result<()> {
+    setup()?;
+
+    let (problem, _) = setup_problem()?;
+    let solution = problem.solve()?;
+    let report = problem.verify(&solution, &Tolerances::default())?;
+    if !report.is_feasible() || report.max_violation() > 1e-9 || report.objective() != solution.objective() {
+        return SolverError::invalid_solution(format!("Unexpected report {:?} for {:?}",
+                                                     report, solution));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn verify_violations() -> Result<()> {
+    setup()?;
+
+    let (problem, vars) = setup_problem()?;
+    let solution = Solution::new(vec![4.0, 1.5, 0.5], None);
+    let report = problem.verify(&solution, &Tolerances::default())?;
+
+    let constraints: Vec<(usize, f64)> = report.constraint_violations().iter()
+        .map(|(constraint, amount)| (constraint.index(), *amount)).collect();
+    if constraints != vec![(0, 1.5), (2, 3.0)] {
+        return SolverError::invalid_solution(format!("Unexpected constraint violations {:?}",
+                                                     constraints));
+    }
+
+    let bounds = report.bound_violations();
+    let integrality = report.integrality_violations();
+    if bounds.len() != 1 || bounds[0].0.index() != vars[1].index() || bounds[0].1 != 0.5 ||
+        integrality.len() != 1 || integrality[0].0.index() != vars[2].index() {
+        return SolverError::invalid_solution(format!("Unexpected report {:?}", report));
+    }
+
+    if report.is_feasible() || report.max_violation() != 3.0 || report.objective() != Some(8.5) {
+        return SolverError::invalid_solution(format!("Unexpected report {:?}", report));
+    }
+
+    let loose = Tolerances { feasibility: 5.0, integrality: 0.5, ..Tolerances::default() };
+    if !problem.verify(&solution, &loose)?.is_feasible() {
+        return SolverError::invalid_solution("Expected solution to be within loose tolerances");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn verify_wrong_size() -> Result<()> {
+    setup()?;
+
+    let (problem, _) = setup_problem()?;
+    let solution = Solution::new(vec![1.0, 0.0], None);
+    match problem.verify(&solution, &Tolerances::default()) {
+        Err(e) if e.kind() == ErrorKind::InvalidSolution => Ok(()),
+        _ => SolverError::invalid_solution("Expected solution with too few values to be rejected"),
+    }
+}